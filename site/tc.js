@@ -74,6 +74,16 @@ let route = (json) => {
         return;
     }
 
+    if ('chat' in json) {
+        showTemporary(json.chat);
+        return;
+    }
+
+    if ('tell' in json) {
+        showTemporary(json.tell);
+        return;
+    }
+
     if ('combat' in json) {
         showCombat(json.combat);
         return;
@@ -84,6 +94,16 @@ let route = (json) => {
         return;
     }
 
+    if ('wallet' in json) {
+        record('wallet', json.wallet);
+        return;
+    }
+
+    if ('equipment' in json) {
+        showEquipment(json.equipment);
+        return;
+    }
+
     if ('space' in json) {
         showSpace(json.space);
         return;
@@ -95,6 +115,12 @@ let route = (json) => {
         return;
     }
 
+    if ('dialogue' in json) {
+        // take over the main description
+        showSpace(json.dialogue);
+        return;
+    }
+
     if ('character' in json) {
         // take over the main description
         showSpace(json.character);
@@ -110,6 +136,16 @@ let route = (json) => {
         showHealth(json.health);
         return;
     }
+
+    if ('who' in json) {
+        showWho(json.who);
+        return;
+    }
+
+    if ('status' in json) {
+        showStatus(json.status);
+        return;
+    }
 }
 
 
@@ -158,6 +194,14 @@ let showInventory = (inventory) => {
     record('inventory', output);
 }
 
+let showEquipment = (equipment) => {
+    let output = "<ul>";
+    equipment.forEach(i => { output += `<li>${i}</li>` });
+    output += "</ul>";
+
+    record('equipment', output);
+}
+
 let showPopulation = (population) => {
     let output = "<ul>"
     population.forEach(i => { output += `<li>${i}</li>` });
@@ -171,15 +215,35 @@ let showTime = (time) => {
     record('time', output);
 }
 
+// the last hearts and conditions shown, since they share the health area
+var hearts = "";
+var conditions = [];
+
 let showHealth = (health) => {
     if (health === 0) {
-        record('health', "💀".repeat(5));
-        return;
+        hearts = "💀".repeat(5);
+    } else {
+        let red_hearts = Math.round(health / 20);
+        let black_hearts = 5 - red_hearts;
+        hearts = `${"❤️".repeat(red_hearts)}${"🖤".repeat(black_hearts)}`;
     }
 
-    let red_hearts = Math.round(health / 20);
-    let black_hearts = 5 - red_hearts;
-    record('health', `${"❤️".repeat(red_hearts)}${"🖤".repeat(black_hearts)}`);
+    recordHealth();
+}
+
+let showStatus = (status) => {
+    conditions = status;
+    recordHealth();
+}
+
+let recordHealth = () => {
+    let output = hearts;
+    conditions.forEach(c => { output += ` <span class="condition">${c}</span>` });
+    record('health', output);
+}
+
+let showWho = (who) => {
+    showTemporary(`Online: ${who.join(", ")}`);
 }
 
 let doAction = (action) => {
@@ -42,16 +42,19 @@ impl Connection {
     }
 
     async fn send_command(&self, input: String) {
-        match Phrase::from(&input) {
-            Some(phrase) => {
-                let action = Command::new(&self.identifier, phrase);
-                let updates = self.world.write().unwrap().command(action).await;
-                // not all updates are for this connection, so we send them over to the delivery actor
-                let delivery = Delivery::from_registry();
-                delivery.do_send(Deliver::new(updates));
+        let phrase = match Phrase::from(&input) {
+            Some(phrase) => phrase,
+            None => {
+                debug!("Received empty message.");
+                return;
             }
-            None => debug!("Received empty message."),
-        }
+        };
+
+        let action = Command::new(&self.identifier, phrase);
+        let updates = self.world.write().unwrap().command(action).await;
+        // not all updates are for this connection, so we send them over to the delivery actor
+        let delivery = Delivery::from_registry();
+        delivery.do_send(Deliver::new(updates));
     }
 }
 
@@ -80,12 +83,26 @@ impl Actor for Connection {
         // register this connection for delivery
         let delivery = Delivery::from_registry();
         delivery.do_send(Register::new(self.identifier.clone(), ctx.address()));
+
+        // and let everyone nearby know they've arrived
+        let arrival = self.world.write().unwrap().connect(&self.identifier);
+        delivery.do_send(Deliver::new(arrival));
     }
 
-    fn stopped(&mut self, _ctx: &mut Self::Context) {
+    fn stopped(&mut self, ctx: &mut Self::Context) {
         info!("🔌🚫 Disconnected!");
         let delivery = Delivery::from_registry();
-        delivery.do_send(Unregister::new(self.identifier.clone()));
+        let unregister = delivery.send(Unregister::new(self.identifier.clone(), ctx.address()));
+
+        // only clean up if the hero hasn't already reconnected on another connection
+        let world = self.world.clone();
+        let identifier = self.identifier.clone();
+        Arbiter::spawn(async move {
+            if let Ok(true) = unregister.await {
+                let updates = world.write().unwrap().disconnect(&identifier);
+                Delivery::from_registry().do_send(Deliver::new(updates));
+            }
+        });
     }
 }
 
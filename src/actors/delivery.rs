@@ -63,10 +63,16 @@ impl Handler<Register> for Delivery {
 }
 
 impl Handler<Unregister> for Delivery {
-    type Result = ();
-    fn handle(&mut self, msg: Unregister, _ctx: &mut Self::Context) {
+    type Result = bool;
+    fn handle(&mut self, msg: Unregister, _ctx: &mut Self::Context) -> bool {
+        // after a reconnect, the old connection closing mustn't cut off the new one
+        if self.addresses.get(&msg.identifier) != Some(&msg.addr) {
+            return false;
+        }
+
         info!("📪 Removing recipient {:?}", msg.identifier);
         self.addresses.remove(&msg.identifier);
+        true
     }
 }
 
@@ -101,17 +107,19 @@ impl Message for Register {
     type Result = ();
 }
 
+/// Removes a connection, returning true if it was still the hero's current one
 #[derive(Debug)]
 pub struct Unregister {
     identifier: Identifier,
+    addr: Addr<Connection>,
 }
 
 impl Unregister {
-    pub fn new(identifier: Identifier) -> Self {
-        Self { identifier }
+    pub fn new(identifier: Identifier, addr: Addr<Connection>) -> Self {
+        Self { identifier, addr }
     }
 }
 
 impl Message for Unregister {
-    type Result = ();
+    type Result = bool;
 }
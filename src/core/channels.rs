@@ -0,0 +1,195 @@
+use crate::core::*;
+
+use std::collections::{BTreeMap, VecDeque};
+
+/// How many messages each channel remembers for scrollback
+const SCROLLBACK: usize = 20;
+
+/// Channels that exist from the moment the world starts
+const DEFAULT_CHANNELS: [&str; 3] = ["ooc", "newbie", "trade"];
+
+/// Global chat channels that any connected hero can join, leave, and talk on.
+///
+/// Channels are independent of spaces: a message on `ooc` reaches every
+/// subscriber, wherever they are in the world. Each channel keeps a bounded
+/// scrollback. Who a hero has muted is saved with the hero (see `Mob::muted`).
+#[derive(Debug)]
+pub struct Channels {
+    channels: BTreeMap<String, Channel>,
+}
+
+#[derive(Debug, Default)]
+struct Channel {
+    subscribers: Vec<Identifier>,
+    history: VecDeque<(Identifier, String)>,
+}
+
+impl Default for Channels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Channels {
+    pub fn new() -> Self {
+        let channels = DEFAULT_CHANNELS
+            .iter()
+            .map(|name| (name.to_string(), Channel::default()))
+            .collect();
+
+        Self { channels }
+    }
+
+    /// Returns true if there's a channel with the given name
+    pub fn contains(&self, channel: &str) -> bool {
+        self.channels.contains_key(channel)
+    }
+
+    /// The names of all channels, in alphabetical order
+    pub fn names(&self) -> Vec<String> {
+        self.channels.keys().cloned().collect()
+    }
+
+    /// Returns true if the identifier is subscribed to the channel
+    pub fn is_subscribed(&self, channel: &str, identifier: &Identifier) -> bool {
+        match self.channels.get(channel) {
+            Some(c) => c.subscribers.contains(identifier),
+            None => false,
+        }
+    }
+
+    pub fn join(&mut self, channel: &str, identifier: &Identifier) -> Result<(), TCError> {
+        let channel = self.get_mut(channel)?;
+        channel.subscribers.push(identifier.to_owned());
+        channel.subscribers.sort();
+        channel.subscribers.dedup();
+        Ok(())
+    }
+
+    pub fn leave(&mut self, channel: &str, identifier: &Identifier) -> Result<(), TCError> {
+        let channel = self.get_mut(channel)?;
+        channel.subscribers.retain(|s| s != identifier);
+        Ok(())
+    }
+
+    /// Removes the identifier from every channel
+    pub fn leave_all(&mut self, identifier: &Identifier) {
+        for channel in self.channels.values_mut() {
+            channel.subscribers.retain(|s| s != identifier);
+        }
+    }
+
+    /// Sends a message to every subscriber of the channel that `hears` the sender
+    /// (ie: hasn't muted them), and records it in the channel's scrollback.
+    pub fn send<F: Fn(&Identifier) -> bool>(
+        &mut self,
+        channel_name: &str,
+        from: &Identifier,
+        from_name: &str,
+        message: &str,
+        hears: F,
+    ) -> Result<Vec<Update>, TCError> {
+        if !self.is_subscribed(channel_name, from) {
+            return Err(TCError::User(format!(
+                "You haven't joined {}.",
+                channel_name
+            )));
+        }
+
+        let line = format!("[{}] {}: {}", channel_name, from_name, message);
+
+        let channel = self.get_mut(channel_name)?;
+        channel.history.push_back((from.to_owned(), line.clone()));
+        while channel.history.len() > SCROLLBACK {
+            channel.history.pop_front();
+        }
+
+        let channel = &self.channels[channel_name];
+        let output = channel
+            .subscribers
+            .iter()
+            .filter(|s| hears(s))
+            .map(|s| Update::chat(s, &line))
+            .collect();
+
+        Ok(output)
+    }
+
+    /// The scrollback of the channel without the `muted` heroes, oldest first
+    pub fn history(&self, channel: &str, muted: &[Identifier]) -> Result<Vec<String>, TCError> {
+        let channel = self
+            .channels
+            .get(channel)
+            .ok_or_else(|| TCError::user("There's no channel by that name."))?;
+
+        let output = channel
+            .history
+            .iter()
+            .filter(|(from, _)| !muted.contains(from))
+            .map(|(_, line)| line.clone())
+            .collect();
+
+        Ok(output)
+    }
+
+    fn get_mut(&mut self, channel: &str) -> Result<&mut Channel, TCError> {
+        self.channels
+            .get_mut(channel)
+            .ok_or_else(|| TCError::user("There's no channel by that name."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrollback_is_bounded() {
+        let mut channels = Channels::new();
+        let hero = Identifier::random();
+        channels.join("ooc", &hero).unwrap();
+
+        for i in 0..(SCROLLBACK + 5) {
+            channels
+                .send("ooc", &hero, "Hero", &format!("message {}", i), |_| true)
+                .unwrap();
+        }
+
+        let history = channels.history("ooc", &[]).unwrap();
+        assert_eq!(history.len(), SCROLLBACK);
+        assert_eq!(history.first().unwrap(), "[ooc] Hero: message 5");
+    }
+
+    #[test]
+    fn test_muted_heroes_are_not_heard() {
+        let mut channels = Channels::new();
+        let loud = Identifier::random();
+        let quiet = Identifier::random();
+        channels.join("newbie", &loud).unwrap();
+        channels.join("newbie", &quiet).unwrap();
+
+        let updates = channels
+            .send("newbie", &loud, "Loud", "HELLO", |s| s != &quiet)
+            .unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].to, loud);
+        assert!(channels
+            .history("newbie", std::slice::from_ref(&loud))
+            .unwrap()
+            .is_empty());
+        assert_eq!(channels.history("newbie", &[]).unwrap().len(), 1);
+
+        channels.leave_all(&quiet);
+        assert!(!channels.is_subscribed("newbie", &quiet));
+    }
+
+    #[test]
+    fn test_must_join_to_send() {
+        let mut channels = Channels::new();
+        let hero = Identifier::random();
+        assert!(channels
+            .send("trade", &hero, "Hero", "rocks!", |_| true)
+            .is_err());
+        assert!(channels.join("nope", &hero).is_err());
+    }
+}
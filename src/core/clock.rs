@@ -136,6 +136,10 @@ impl Clock {
         }
     }
 
+    pub fn is_night(&self) -> bool {
+        matches!(self.phase_of_day().0, DayPhase::Night)
+    }
+
     pub fn day_of_year(&self) -> u64 {
         self.tick_of_year() / DAY
     }
@@ -0,0 +1,317 @@
+use crate::core::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Damage rolled by a Mob fighting with nothing but their fists
+pub const UNARMED: &str = "1d2";
+
+/// One way a Mob can attack (eg: a mosquito's bite), as declared in its template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attack {
+    /// What the attack is called in combat messages (eg: "bite")
+    pub name: String,
+
+    /// The kind of damage the attack does
+    pub kind: DamageKind,
+
+    /// A dice string for the damage (eg: "1d4+1")
+    pub damage: String,
+
+    /// How likely this attack is to be chosen, relative to the Mob's other attacks
+    pub weight: usize,
+}
+
+/// How well a Mob (or the Items they wear) stands up to a kind of damage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resistance {
+    Immune,
+    Resistant,
+    Vulnerable,
+}
+
+impl Resistance {
+    /// Maps the names builders use in templates (eg: "vulnerable") onto a resistance
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_ref() {
+            "immune" | "immunity" => Some(Self::Immune),
+            "resistant" | "resist" | "resistance" => Some(Self::Resistant),
+            "vulnerable" | "vulnerability" => Some(Self::Vulnerable),
+            _ => None,
+        }
+    }
+
+    /// Adjusts an amount of damage: immunity ignores it, resistance halves it,
+    /// and vulnerability doubles it.
+    pub fn apply(self, hp: usize) -> usize {
+        match self {
+            Self::Immune => 0,
+            Self::Resistant => hp / 2,
+            Self::Vulnerable => hp * 2,
+        }
+    }
+
+    /// Combines several resistances to the same kind of damage. Immunity always wins,
+    /// and resistance and vulnerability cancel each other out.
+    pub fn combine<I: IntoIterator<Item = Resistance>>(resistances: I) -> Option<Self> {
+        let resistances: Vec<Resistance> = resistances.into_iter().collect();
+
+        let resistant = resistances.contains(&Self::Resistant);
+        let vulnerable = resistances.contains(&Self::Vulnerable);
+
+        if resistances.contains(&Self::Immune) {
+            Some(Self::Immune)
+        } else if resistant && !vulnerable {
+            Some(Self::Resistant)
+        } else if vulnerable && !resistant {
+            Some(Self::Vulnerable)
+        } else {
+            None
+        }
+    }
+}
+
+/// The attack used by Mobs that don't have any of their own
+pub fn unarmed() -> Attack {
+    Attack {
+        name: "punch".to_owned(),
+        kind: DamageKind::Blunt,
+        damage: UNARMED.to_owned(),
+        weight: 1,
+    }
+}
+
+/// Picks one of the attacks at random, favouring those with more weight
+pub fn choose<'a>(attacks: &'a [Attack], dice: &mut Dice) -> Option<&'a Attack> {
+    let total: usize = attacks.iter().map(|a| a.weight).sum();
+    if total == 0 {
+        return attacks.first();
+    }
+
+    let mut pick = dice.range(0, total);
+    for attack in attacks {
+        if pick < attack.weight {
+            return Some(attack);
+        }
+        pick -= attack.weight;
+    }
+
+    None
+}
+
+/// The outcome of an attack roll
+#[derive(Debug, PartialEq, Eq)]
+pub enum Hit {
+    Miss,
+    Hit,
+    Critical,
+}
+
+/// The bonus (or penalty) an attribute gives to rolls. Ten is average, and
+/// every two points above or below adds or removes one.
+pub fn modifier(attribute: usize) -> isize {
+    (attribute as isize - 10).div_euclid(2)
+}
+
+/// Rolls a d20 to see whether the attacker hits the defender. The attacker's
+/// dexterity (plus any blessing, and their weapon's enchantment) is added to the
+/// roll, which needs to beat ten plus the defender's dexterity. Both are hampered
+/// by heavy loads. A natural 1 always misses, and a natural 20 is a critical hit.
+pub fn attack_roll(attacker: &Mob, defender: &Mob, dice: &mut Dice) -> Hit {
+    match dice.d(20, 1) {
+        1 => Hit::Miss,
+        20 => Hit::Critical,
+        roll => {
+            let blessing = attacker.statuses.potency(Condition::Blessed) as isize;
+            let dexterity = modifier(attacker.attribute(Attribute::Dexterity));
+            let total = roll as isize + dexterity + blessing + enchantment(attacker)
+                - attacker.encumbrance().penalty();
+            let defense = 10 + modifier(defender.attribute(Attribute::Dexterity))
+                - defender.encumbrance().penalty();
+
+            if total >= defense {
+                Hit::Hit
+            } else {
+                Hit::Miss
+            }
+        }
+    }
+}
+
+/// Rolls a d20 to see whether a Mob gets away from a fight, adding their dexterity
+/// and taking off a penalty for how much they're carrying
+pub fn flee_roll(mob: &Mob, dice: &mut Dice) -> bool {
+    let dexterity = modifier(mob.attribute(Attribute::Dexterity));
+    dice.d(20, 1) as isize + dexterity - mob.encumbrance().penalty() >= 10
+}
+
+/// Rolls a d20 to see whether the victor accepts a surrender. The yielder's charisma
+/// is added to the roll, which needs to beat ten plus the victor's wisdom.
+pub fn surrender_roll(yielder: &Mob, victor: &Mob, dice: &mut Dice) -> bool {
+    dice.d(20, 1) as isize + modifier(yielder.attribute(Attribute::Charisma))
+        >= 10 + modifier(victor.attribute(Attribute::Wisdom))
+}
+
+/// The magic in the weapon the Mob is wielding, if any
+fn enchantment(mob: &Mob) -> isize {
+    mob.equipment.weapon().map_or(0, |w| w.enchantment as isize)
+}
+
+/// Rolls damage from a dice string (eg: "1d6"), adding the attacker's strength
+/// and any enchantment on their weapon.
+/// Critical hits roll the dice twice. A hit always does at least one point of damage.
+pub fn damage_roll(attacker: &Mob, damage: &str, critical: bool, dice: &mut Dice) -> usize {
+    let mut rolled = dice.roll(damage).unwrap_or(1) as isize;
+    if critical {
+        rolled += dice.roll(damage).unwrap_or(1) as isize;
+    }
+
+    std::cmp::max(
+        rolled + modifier(attacker.attribute(Attribute::Strength)) + enchantment(attacker),
+        1,
+    ) as usize
+}
+
+/// Resolves a single attack from the attacker against the defender
+pub fn resolve(attacker: &Mob, defender: &Mob, attack: &Attack, dice: &mut Dice) -> Effect {
+    match attack_roll(attacker, defender, dice) {
+        Hit::Miss => Effect::Miss,
+        Hit::Hit => {
+            let hp = damage_roll(attacker, &attack.damage, false, dice);
+            Effect::Harm(attack.kind.damage(hp))
+        }
+        Hit::Critical => {
+            let hp = damage_roll(attacker, &attack.damage, true, dice);
+            Effect::Critical(attack.kind.damage(hp))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mob(strength: usize, dexterity: usize) -> Mob {
+        let mut mob = Mob::new();
+        mob.strength = strength;
+        mob.dexterity = dexterity;
+        mob
+    }
+
+    #[test]
+    fn test_modifier() {
+        assert_eq!(modifier(10), 0);
+        assert_eq!(modifier(11), 0);
+        assert_eq!(modifier(12), 1);
+        assert_eq!(modifier(9), -1);
+        assert_eq!(modifier(5), -3);
+        assert_eq!(modifier(1), -5);
+    }
+
+    #[test]
+    fn test_seeded_combat_is_deterministic() {
+        let hero = mob(10, 10);
+        let mosquito = mob(1, 5);
+
+        let mut left = Dice::seeded(7);
+        let mut right = Dice::seeded(7);
+
+        for _ in 0..100 {
+            let a = format!("{:?}", resolve(&hero, &mosquito, &unarmed(), &mut left));
+            let b = format!("{:?}", resolve(&hero, &mosquito, &unarmed(), &mut right));
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn test_dexterity_matters() {
+        let nimble = mob(10, 18);
+        let clumsy = mob(10, 2);
+        let mut dice = Dice::seeded(1);
+
+        let mut nimble_hits = 0;
+        let mut clumsy_hits = 0;
+        for _ in 0..200 {
+            if attack_roll(&nimble, &clumsy, &mut dice) != Hit::Miss {
+                nimble_hits += 1;
+            }
+            if attack_roll(&clumsy, &nimble, &mut dice) != Hit::Miss {
+                clumsy_hits += 1;
+            }
+        }
+
+        assert!(nimble_hits > clumsy_hits);
+    }
+
+    #[test]
+    fn test_flee_and_surrender_rolls() {
+        let mut dice = Dice::seeded(3);
+
+        // the nimble always get away, the clumsy only sometimes
+        let nimble = mob(10, 30);
+        let clumsy = mob(10, 1);
+        assert!((0..50).all(|_| flee_roll(&nimble, &mut dice)));
+        assert!((0..50).any(|_| !flee_roll(&clumsy, &mut dice)));
+
+        // a charming yielder always talks their way out against a dull victor,
+        // but a wise victor never accepts a surrender from a boor
+        let mut charming = mob(10, 10);
+        charming.charisma = 30;
+        let mut boor = mob(10, 10);
+        boor.charisma = 1;
+        let mut dull = mob(10, 10);
+        dull.wisdom = 1;
+        let mut wise = mob(10, 10);
+        wise.wisdom = 30;
+
+        assert!((0..50).all(|_| surrender_roll(&charming, &dull, &mut dice)));
+        assert!((0..50).all(|_| !surrender_roll(&boor, &wise, &mut dice)));
+    }
+
+    #[test]
+    fn test_choose_by_weight() {
+        let mut bite = unarmed();
+        bite.name = "bite".to_owned();
+        bite.weight = 9;
+
+        let attacks = vec![unarmed(), bite];
+        let mut dice = Dice::seeded(5);
+
+        let bites = (0..100)
+            .filter(|_| choose(&attacks, &mut dice).unwrap().name == "bite")
+            .count();
+
+        assert!(bites > 50);
+        assert!(choose(&[], &mut dice).is_none());
+    }
+
+    #[test]
+    fn test_resistances() {
+        use Resistance::*;
+
+        assert_eq!(Resistance::combine(vec![]), None);
+        assert_eq!(
+            Resistance::combine(vec![Resistant, Immune, Vulnerable]),
+            Some(Immune)
+        );
+        assert_eq!(
+            Resistance::combine(vec![Resistant, Resistant]),
+            Some(Resistant)
+        );
+        assert_eq!(Resistance::combine(vec![Resistant, Vulnerable]), None);
+
+        assert_eq!(Immune.apply(10), 0);
+        assert_eq!(Resistant.apply(5), 2);
+        assert_eq!(Vulnerable.apply(5), 10);
+    }
+
+    #[test]
+    fn test_damage_is_at_least_one() {
+        let weakling = mob(1, 10);
+        let mut dice = Dice::seeded(3);
+
+        for _ in 0..50 {
+            assert!(damage_roll(&weakling, UNARMED, false, &mut dice) >= 1);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::core::Identifier;
+
+use serde::{Deserialize, Serialize};
+
+/// The kinds of consumables, which decide the commands that use them up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConsumableKind {
+    Food,
+    Drink,
+    Potion,
+    Scroll,
+}
+
+impl ConsumableKind {
+    /// Maps the names builders use in templates (eg: "potion") onto a kind of consumable
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_ref() {
+            "food" | "meal" => Some(Self::Food),
+            "drink" => Some(Self::Drink),
+            "potion" | "elixir" => Some(Self::Potion),
+            "scroll" | "book" => Some(Self::Scroll),
+            _ => None,
+        }
+    }
+
+    /// Can this kind of consumable be used with the verb (eg: "QUAFF")?
+    pub fn accepts(self, verb: &str) -> bool {
+        match verb.to_uppercase().as_ref() {
+            "EAT" => self == Self::Food,
+            "DRINK" => self == Self::Drink || self == Self::Potion,
+            "QUAFF" => self == Self::Potion,
+            "READ" => self == Self::Scroll,
+            _ => false,
+        }
+    }
+}
+
+/// What happens when a consumable is used up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemEffect {
+    /// Restores some health
+    Heal(usize),
+
+    /// Cures poison, and restores some health
+    Antidote(usize),
+
+    /// Restores all health, and cures every condition
+    Restore,
+
+    /// Blesses the user for a number of ticks
+    Bless { potency: usize, ticks: usize },
+
+    /// Whisks the user away to another space
+    Teleport(Identifier),
+}
+
+/// Food, drink, potions and scrolls: Items that are used up, with effects
+/// declared in their templates
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Consumable {
+    pub kind: ConsumableKind,
+    pub effects: Vec<ItemEffect>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbs() {
+        assert!(ConsumableKind::Food.accepts("eat"));
+        assert!(!ConsumableKind::Food.accepts("drink"));
+        assert!(ConsumableKind::Potion.accepts("DRINK"));
+        assert!(ConsumableKind::Potion.accepts("quaff"));
+        assert!(!ConsumableKind::Drink.accepts("quaff"));
+        assert!(ConsumableKind::Scroll.accepts("read"));
+        assert_eq!(
+            ConsumableKind::from_name("Elixir"),
+            Some(ConsumableKind::Potion)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use crate::core::combat::modifier;
+use crate::core::*;
+
+use std::time::Duration;
+
+/// Something heroes can make, declared in a template under `recipes/`
+#[derive(Debug, Default, Clone)]
+pub struct Recipe {
+    /// What heroes call it when they `CRAFT` it
+    pub name: String,
+    pub description: String,
+
+    /// Item prototypes used up in crafting, and how many of each
+    pub inputs: Vec<(String, usize)>,
+
+    /// Item prototypes that have to be carried, but aren't used up
+    pub tools: Vec<String>,
+
+    /// The attribute crafting relies on, and the least a hero needs to try
+    pub skill: Option<(Attribute, usize)>,
+
+    /// A d20 plus the skill modifier has to reach this, or the inputs are
+    /// wasted. Zero never fails.
+    pub difficulty: usize,
+
+    /// The Item prototype made, and how many
+    pub output: String,
+    pub quantity: usize,
+
+    /// How long the hero is busy crafting
+    pub time: Duration,
+}
+
+impl Recipe {
+    /// The first input (or tool) the mob doesn't have enough of
+    pub fn missing(&self, mob: &Mob) -> Option<&str> {
+        let inputs = self
+            .inputs
+            .iter()
+            .find(|(prototype, count)| mob.inventory.count(prototype) < *count)
+            .map(|(prototype, _)| prototype);
+
+        let tools = self
+            .tools
+            .iter()
+            .find(|prototype| mob.inventory.count(prototype) == 0);
+
+        inputs.or(tools).map(|p| p.as_ref())
+    }
+
+    /// Does the mob have the skill to try this at all?
+    pub fn is_skilled(&self, mob: &Mob) -> bool {
+        match self.skill {
+            Some((attribute, level)) => mob.attribute(attribute) >= level,
+            None => true,
+        }
+    }
+
+    /// Rolls to see whether the crafting works out
+    pub fn succeeds(&self, mob: &Mob, dice: &mut Dice) -> bool {
+        if self.difficulty == 0 {
+            return true;
+        }
+
+        let bonus = self
+            .skill
+            .map(|(attribute, _)| modifier(mob.attribute(attribute)))
+            .unwrap_or(0);
+
+        dice.d(20, 1) as isize + bonus >= self.difficulty as isize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(prototype: &str) -> Item {
+        let mut item = Item::new();
+        item.prototype = prototype.to_owned();
+        item.name = prototype.to_lowercase();
+        item
+    }
+
+    #[test]
+    fn test_requirements() {
+        let recipe = Recipe {
+            name: "spear".to_owned(),
+            inputs: vec![("STICK".to_owned(), 1)],
+            tools: vec!["ROCK".to_owned()],
+            skill: Some((Attribute::Dexterity, 8)),
+            difficulty: 30,
+            output: "SPEAR".to_owned(),
+            quantity: 1,
+            ..Recipe::default()
+        };
+
+        let mut hero = Mob::new();
+        hero.dexterity = 6;
+        assert!(!recipe.is_skilled(&hero));
+        assert_eq!(recipe.missing(&hero), Some("STICK"));
+
+        hero.dexterity = 10;
+        hero.inventory.add(item("STICK"));
+        assert!(recipe.is_skilled(&hero));
+        assert_eq!(recipe.missing(&hero), Some("ROCK"));
+
+        hero.inventory.add(item("ROCK"));
+        assert_eq!(recipe.missing(&hero), None);
+
+        // nobody rolls a 30 on a d20
+        let mut dice = Dice::seeded(1);
+        assert!(!recipe.succeeds(&hero, &mut dice));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::core::*;
+
+use std::collections::HashMap;
+
+/// The node every conversation starts at
+pub const START: &str = "start";
+
+/// A conversation tree for one Mob prototype, made up of named nodes
+#[derive(Debug, Default, Clone)]
+pub struct Dialogue {
+    pub nodes: HashMap<String, Node>,
+}
+
+impl Dialogue {
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+}
+
+/// One thing an NPC says, what happens when they say it, and how the hero can answer
+#[derive(Debug, Default, Clone)]
+pub struct Node {
+    pub text: String,
+    pub choices: Vec<Choice>,
+    pub consequences: Vec<Consequence>,
+}
+
+impl Node {
+    /// The choices the hero can pick, numbered from one (as they're typed in `TALK`)
+    pub fn available(&self, hero: &Mob) -> Vec<(usize, &Choice)> {
+        self.choices
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.is_available(hero))
+            .map(|(idx, c)| (idx + 1, c))
+            .collect()
+    }
+}
+
+/// An answer the hero can give, leading to another node
+#[derive(Debug, Default, Clone)]
+pub struct Choice {
+    pub label: String,
+    pub goto: String,
+    pub requirements: Vec<Requirement>,
+}
+
+impl Choice {
+    pub fn is_available(&self, hero: &Mob) -> bool {
+        self.requirements.iter().all(|r| r.is_met(hero))
+    }
+}
+
+/// Something that has to be true of the hero for a choice to show up
+#[derive(Debug, Clone)]
+pub enum Requirement {
+    /// Carrying an item with this prototype
+    Item(String),
+    /// Has this flag set
+    Flag(String),
+    /// Doesn't have this flag set
+    NotFlag(String),
+}
+
+impl Requirement {
+    pub fn is_met(&self, hero: &Mob) -> bool {
+        match self {
+            Self::Item(prototype) => hero.inventory.count(prototype) > 0,
+            Self::Flag(flag) => hero.has_flag(flag),
+            Self::NotFlag(flag) => !hero.has_flag(flag),
+        }
+    }
+}
+
+/// Something that happens when the conversation reaches a node
+#[derive(Debug, Clone)]
+pub enum Consequence {
+    /// The NPC hands the hero an item with this prototype
+    GiveItem(String),
+    /// Sets a flag on the hero
+    SetFlag(String),
+    /// Adds a quest to the hero's log
+    StartQuest(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requirements() {
+        let node = Node {
+            text: "Bzz?".to_owned(),
+            choices: vec![
+                Choice {
+                    label: "Hello".to_owned(),
+                    goto: "hello".to_owned(),
+                    requirements: vec![Requirement::NotFlag("met".to_owned())],
+                },
+                Choice {
+                    label: "Hello again".to_owned(),
+                    goto: "again".to_owned(),
+                    requirements: vec![Requirement::Flag("met".to_owned())],
+                },
+                Choice {
+                    label: "Want a rock?".to_owned(),
+                    goto: "rock".to_owned(),
+                    requirements: vec![Requirement::Item("ROCK".to_owned())],
+                },
+            ],
+            consequences: vec![],
+        };
+
+        let mut hero = Mob::new();
+        let numbers =
+            |hero: &Mob| -> Vec<usize> { node.available(hero).iter().map(|(n, _)| *n).collect() };
+
+        assert_eq!(numbers(&hero), vec![1]);
+
+        hero.set_flag("met");
+        let mut rock = Item::new();
+        rock.prototype = "ROCK".to_owned();
+        hero.inventory.add(rock);
+
+        assert_eq!(numbers(&hero), vec![2, 3]);
+    }
+}
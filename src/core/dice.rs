@@ -1,4 +1,5 @@
 use rand::prelude::*;
+use rand::rngs::StdRng;
 
 /// # Dice
 ///
@@ -32,17 +33,44 @@ use rand::prelude::*;
 /// assert!(value >= 5);
 /// assert!(value <= 5*6);
 /// # }
+///
+/// // flat bonuses and plain numbers work too
+/// let bonus = dice.roll("1d4+2").unwrap();
+/// assert!(bonus >= 3 && bonus <= 6);
+/// let base = dice.roll("20+1d10").unwrap();
+/// assert!(base >= 21 && base <= 30);
+/// assert_eq!(dice.roll("3").unwrap(), 3);
+/// assert!(dice.roll("banana").is_err());
+/// assert!(Dice::check("1d0").is_err());
+///
+/// // seeded dice always roll the same way, which is handy for testing
+/// let mut left = Dice::seeded(42);
+/// let mut right = Dice::seeded(42);
+/// assert_eq!(left.d(20, 10), right.d(20, 10));
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Dice {
-    rng: rand::rngs::ThreadRng,
+    rng: StdRng,
+}
+
+impl Default for Dice {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Dice {
-    /// Instantiates with a fresh copy of `thread_rng()`
+    /// Instantiates with a generator seeded from `thread_rng()`
     pub fn new() -> Self {
         Self {
-            rng: rand::thread_rng(),
+            rng: StdRng::from_rng(rand::thread_rng()).expect("Failed to seed dice!"),
+        }
+    }
+
+    /// Instantiates with a fixed seed, producing the same sequence of rolls every time
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
         }
     }
 
@@ -61,16 +89,52 @@ impl Dice {
         sum
     }
 
-    /// Rolls a standard roll string (eg: `roll("3d6")` rolls a six sided die three times)
+    /// Rolls a standard roll string (eg: `roll("3d6")` rolls a six sided die three times).
+    /// Flat bonuses and more dice can be added on either side (eg: `roll("1d4+1")`, or
+    /// `roll("20+1d10")`), and a plain number (eg: `roll("2")`) always rolls itself.
     pub fn roll(&mut self, input: &str) -> Result<usize, DiceErr> {
-        let mut vs: Vec<&str> = input.split('d').collect();
-        let raw_sides = vs.pop().ok_or_else(|| DiceErr::BadDiceString)?;
-        let raw_count = vs.pop().ok_or_else(|| DiceErr::BadDiceString)?;
+        let (dice, bonus) = Self::parse(input)?;
 
-        let sides = raw_sides.parse().map_err(|_| DiceErr::BadDiceString)?;
-        let count = raw_count.parse().map_err(|_| DiceErr::BadDiceString)?;
+        let mut sum = bonus;
+        for (count, sides) in dice {
+            sum = sum.saturating_add(self.d(sides, count));
+        }
+
+        Ok(sum)
+    }
+
+    /// Checks a roll string without rolling it (eg: when loading templates)
+    pub fn check(input: &str) -> Result<(), DiceErr> {
+        Self::parse(input).map(|_| ())
+    }
+
+    /// Splits a roll string into the dice to roll (how many, and how many sides) and a flat bonus
+    fn parse(input: &str) -> Result<(Vec<(usize, usize)>, usize), DiceErr> {
+        let mut dice = vec![];
+        let mut bonus: usize = 0;
+
+        for term in input.split('+').map(|t| t.trim()) {
+            match term.split_once('d') {
+                // no dice, just a number
+                None => {
+                    let value: usize = term.parse().map_err(|_| DiceErr::BadDiceString)?;
+                    bonus = bonus.saturating_add(value);
+                }
+                Some((raw_count, raw_sides)) => {
+                    let count = raw_count.parse().map_err(|_| DiceErr::BadDiceString)?;
+                    let sides = raw_sides.parse().map_err(|_| DiceErr::BadDiceString)?;
+
+                    // there's no rolling a die without sides
+                    if sides == 0 {
+                        return Err(DiceErr::BadDiceString);
+                    }
+
+                    dice.push((count, sides));
+                }
+            }
+        }
 
-        Ok(self.d(sides, count))
+        Ok((dice, bonus))
     }
 }
 
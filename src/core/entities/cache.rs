@@ -33,6 +33,29 @@ impl<T: Tickable> Cache<T> {
     }
 }
 
+impl<T: Clone> Cache<T> {
+    /// Returns a copy of the first item matching the predicate
+    pub fn find<P: Fn(&T) -> bool>(&self, predicate: P) -> Option<T> {
+        self.items
+            .read()
+            .unwrap()
+            .values()
+            .find(|i| predicate(i))
+            .cloned()
+    }
+
+    /// Returns copies of every item matching the predicate
+    pub fn select<P: Fn(&T) -> bool>(&self, predicate: P) -> Vec<T> {
+        self.items
+            .read()
+            .unwrap()
+            .values()
+            .filter(|i| predicate(i))
+            .cloned()
+            .collect()
+    }
+}
+
 impl<F: Melee> Cache<F> {
     pub fn melee(&self, world: &World, dice: &mut Dice) -> Vec<Update> {
         self.items
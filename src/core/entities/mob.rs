@@ -7,6 +7,15 @@ use serde::{Deserialize, Serialize};
 
 use std::time::{Duration, Instant};
 
+/// The share of their experience (in percent) a hero loses when they die
+const DEATH_PENALTY: usize = 10;
+
+/// How much weight a Mob can carry for each point of strength
+const CARRY_PER_STRENGTH: usize = 10;
+
+/// How much bulk a Mob can hold in their hands and pockets. Containers help.
+pub const MAX_BULK: usize = 20;
+
 /// Represents a mobile characters in the game, including PCs (Player Characters) and NPCs (Non-Player Characters).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Mob {
@@ -46,17 +55,123 @@ pub struct Mob {
     /// Charisma attribute
     pub charisma: usize,
 
+    /// Experience earned by defeating other Mobs
+    #[serde(default)]
+    pub experience: usize,
+
+    /// How this Mob attacks; Mobs without attacks fight unarmed
+    #[serde(default)]
+    pub attacks: Vec<Attack>,
+
+    /// How many attacks this Mob makes in each round of melee
+    #[serde(default)]
+    pub attacks_per_round: usize,
+
+    /// Kinds of damage this Mob is immune, resistant, or vulnerable to
+    #[serde(default)]
+    pub resistances: Vec<(DamageKind, Resistance)>,
+
     /// The inventory for this Mob
     pub inventory: Inventory,
 
+    /// What this Mob is wearing and wielding
+    #[serde(default)]
+    pub equipment: Equipment,
+
+    /// The money this Mob carries
+    #[serde(default)]
+    pub wallet: Wallet,
+
+    /// What the Mob buys and sells, if they keep a shop
+    #[serde(default)]
+    pub shop: Option<Shop>,
+
     /// A unique, sorted list of Mobs that will be attacked on sight
     pub enemies: Vec<Identifier>,
 
+    /// A unique, sorted list of story flags set on this Mob (eg: by dialogue)
+    #[serde(default)]
+    pub flags: Vec<String>,
+
+    /// Quests this hero has taken on, in the order they were started
+    #[serde(default)]
+    pub quests: Vec<String>,
+
+    /// The NPC this Mob is talking to, and where the conversation is at
+    #[serde(skip)]
+    pub conversation: Option<(Identifier, String)>,
+
+    /// A unique, sorted list of heroes whose tells are refused
+    #[serde(default)]
+    pub blocked: Vec<Identifier>,
+
+    /// A unique, sorted list of heroes whose channel messages are hidden
+    #[serde(default)]
+    pub muted: Vec<Identifier>,
+
+    /// The last hero to send this Mob a tell, used by `REPLY`
+    #[serde(skip)]
+    pub reply_to: Option<Identifier>,
+
+    /// The Mob this one follows from space to space
+    #[serde(skip)]
+    pub following: Option<Identifier>,
+
+    /// How an NPC acts on its own (eg: attacking heroes on sight)
+    #[serde(default)]
+    pub behaviour: Behaviour,
+
+    /// How an NPC moves around the world on its own
+    #[serde(default)]
+    pub movement: Movement,
+
+    /// Where an NPC was spawned, and returns to if it's a homebody
+    #[serde(skip)]
+    pub home: Option<Identifier>,
+
+    /// How far along its patrol route an NPC is
+    #[serde(skip)]
+    pub patrol_step: usize,
+
+    /// Below this percentage of health, the Mob tries to flee (zero never flees)
+    #[serde(default)]
+    pub wimpy: usize,
+
+    /// Set when the Mob drops below their wimpy threshold in melee
+    #[serde(skip)]
+    pub fleeing: bool,
+
+    /// The enemy this Mob has offered to stop fighting, used by `YIELD`
+    #[serde(skip)]
+    pub yielding_to: Option<Identifier>,
+
+    /// Dead heroes linger as ghosts until they respawn
+    #[serde(default)]
+    pub ghost: bool,
+
+    /// Whether this hero hears about other heroes arriving and leaving
+    #[serde(default)]
+    pub notify: bool,
+
+    /// Why the hero is away from the keyboard, if they are
+    #[serde(skip)]
+    pub away: Option<String>,
+
+    /// When the hero last did anything, used by `WHO`
+    #[serde(skip)]
+    pub active: Option<Instant>,
+
+    /// Timed conditions like poison or stun, which follow a hero through logging out
+    #[serde(default)]
+    pub statuses: Statuses,
+
     /// Determines whether a Mob is currently busy with an activity
     #[serde(skip)]
     pub delay: Option<Instant>,
 
-    /// What the Mob is currently doing
+    /// What the Mob is currently doing. Not saved, since the delay isn't either:
+    /// a hero who logs out mid-craft still has the materials to start again.
+    #[serde(skip)]
     pub doing: Doing,
 }
 
@@ -69,6 +184,9 @@ impl Mob {
             description: Description::default(),
             space_id: Identifier::origin(),
             inventory: Inventory::new(),
+            equipment: Equipment::new(),
+            wallet: Wallet::new(),
+            shop: None,
 
             strength: 0,
             constitution: 0,
@@ -77,13 +195,40 @@ impl Mob {
             wisdom: 0,
             charisma: 0,
             hp: 0,
+            experience: 0,
+            attacks: vec![],
+            attacks_per_round: 1,
+            resistances: vec![],
 
             enemies: vec![],
+            flags: vec![],
+            quests: vec![],
+            conversation: None,
+            blocked: vec![],
+            muted: vec![],
+            reply_to: None,
+            following: None,
+            behaviour: Behaviour::default(),
+            movement: Movement::default(),
+            home: None,
+            patrol_step: 0,
+            wimpy: 0,
+            fleeing: false,
+            yielding_to: None,
+            ghost: false,
+            notify: false,
+            away: None,
+            active: None,
+            statuses: Statuses::new(),
             delay: None,
             doing: Doing::Nothing,
         }
     }
 
+    pub fn is_hero(&self) -> bool {
+        self.prototype.eq("HERO")
+    }
+
     pub fn add_enemy(&mut self, identifier: &Identifier) {
         self.enemies.push(identifier.to_owned());
         self.enemies.sort();
@@ -99,6 +244,54 @@ impl Mob {
             .collect();
     }
 
+    pub fn block(&mut self, identifier: &Identifier) {
+        self.blocked.push(identifier.to_owned());
+        self.blocked.sort();
+        self.blocked.dedup();
+    }
+
+    pub fn unblock(&mut self, identifier: &Identifier) {
+        self.blocked.retain(|b| b != identifier);
+    }
+
+    pub fn is_blocking(&self, identifier: &Identifier) -> bool {
+        self.blocked.contains(identifier)
+    }
+
+    pub fn mute(&mut self, identifier: &Identifier) {
+        self.muted.push(identifier.to_owned());
+        self.muted.sort();
+        self.muted.dedup();
+    }
+
+    pub fn unmute(&mut self, identifier: &Identifier) {
+        self.muted.retain(|m| m != identifier);
+    }
+
+    pub fn is_muting(&self, identifier: &Identifier) -> bool {
+        self.muted.contains(identifier)
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    pub fn set_flag(&mut self, flag: &str) {
+        self.flags.push(flag.to_owned());
+        self.flags.sort();
+        self.flags.dedup();
+    }
+
+    /// Adds a quest to the log, returning true if it's new
+    pub fn start_quest(&mut self, quest: &str) -> bool {
+        if self.quests.iter().any(|q| q == quest) {
+            return false;
+        }
+
+        self.quests.push(quest.to_owned());
+        true
+    }
+
     pub fn doing(&self) -> &Doing {
         if self.is_busy() {
             &self.doing
@@ -119,12 +312,155 @@ impl Mob {
         false
     }
 
+    /// An attribute, including bonuses (and penalties) from equipped Items
+    pub fn attribute(&self, attribute: Attribute) -> usize {
+        let base = match attribute {
+            Attribute::Strength => self.strength,
+            Attribute::Constitution => self.constitution,
+            Attribute::Dexterity => self.dexterity,
+            Attribute::Intelligence => self.intelligence,
+            Attribute::Wisdom => self.wisdom,
+            Attribute::Charisma => self.charisma,
+        };
+
+        std::cmp::max(base as isize + self.equipment.bonus(attribute), 0) as usize
+    }
+
+    /// Constitution, counting equipment, but never less than 1 however cursed the gear
     pub fn max_health(&self) -> usize {
-        self.constitution
+        std::cmp::max(1, self.attribute(Attribute::Constitution))
     }
 
     pub fn health(&self) -> usize {
-        (self.hp * 100) / self.constitution
+        (self.hp * 100) / self.max_health()
+    }
+
+    /// How this Mob stands up to a kind of damage, counting the Items it wears
+    pub fn resistance(&self, kind: DamageKind) -> Option<Resistance> {
+        let own = self.resistances.iter();
+        let worn = self
+            .equipment
+            .items()
+            .iter()
+            .filter(|i| !i.is_broken())
+            .flat_map(|i| i.resistances.iter());
+
+        Resistance::combine(own.chain(worn).filter(|(k, _)| *k == kind).map(|(_, r)| *r))
+    }
+
+    /// The most weight this Mob can carry, worn and held
+    pub fn carry_limit(&self) -> usize {
+        self.attribute(Attribute::Strength) * CARRY_PER_STRENGTH
+    }
+
+    /// The weight of everything this Mob carries and wears
+    pub fn load(&self) -> usize {
+        self.inventory.weight()
+            + self
+                .equipment
+                .items()
+                .iter()
+                .map(|i| i.total_weight())
+                .sum::<usize>()
+    }
+
+    pub fn encumbrance(&self) -> Encumbrance {
+        Encumbrance::from_load(self.load(), self.carry_limit())
+    }
+
+    /// Finishes crafting, using up the inputs and rolling to see if it worked. The
+    /// inputs stay with the hero until now, so nothing's lost to an unfinished craft.
+    fn craft(&mut self, name: &str, world: &World, dice: &mut Dice) -> Vec<Update> {
+        let recipe = match world.recipes.get(name) {
+            Some(recipe) => recipe,
+            None => return vec![],
+        };
+
+        if !self.is_alive() || self.ghost {
+            return vec![];
+        }
+
+        if recipe.missing(self).is_some() {
+            return vec![Update::info(
+                self.identifier(),
+                &format!(
+                    "You no longer have what you need to make a {}.",
+                    recipe.name
+                ),
+            )];
+        }
+
+        let before = self.encumbrance();
+        for (prototype, count) in &recipe.inputs {
+            self.inventory.take_prototype(prototype, *count);
+        }
+
+        if !recipe.succeeds(self, dice) {
+            return vec![
+                Update::info(
+                    self.identifier(),
+                    &format!("You botch the {}, wasting the materials.", recipe.name),
+                ),
+                Update::inventory(self.identifier(), &self.inventory),
+            ];
+        }
+
+        for _ in 0..std::cmp::max(recipe.quantity, 1) {
+            if let Some(item) = world.item_prototypes.create(&recipe.output, dice) {
+                self.inventory.add(item);
+            }
+        }
+
+        let mut output = vec![Update::info(
+            self.identifier(),
+            &format!("You finish making a {}.", recipe.name),
+        )];
+        if self.encumbrance() != before {
+            output.push(Update::info(
+                self.identifier(),
+                self.encumbrance().message(),
+            ));
+        }
+        output.push(Update::inventory(self.identifier(), &self.inventory));
+
+        output
+    }
+
+    /// Tops up a shopkeeper's stock, a 1 in `chance` chance per ware each tick
+    fn restock(&mut self, world: &World, dice: &mut Dice) {
+        let wares = match &self.shop {
+            Some(shop) => shop.wares.clone(),
+            None => return,
+        };
+
+        for ware in wares {
+            let spawn = &ware.restock;
+            if self.inventory.count(&spawn.name) < spawn.max && spawn.should_spawn(dice) {
+                if let Some(item) = world.item_prototypes.create(&spawn.name, dice) {
+                    self.inventory.add(item);
+                }
+            }
+        }
+    }
+
+    /// Checks that this Mob has the strength (and the room) to pick up the items
+    pub fn can_carry(&self, items: &[Item]) -> Result<(), TCError> {
+        let weight: usize = items.iter().map(|i| i.total_weight()).sum();
+        let bulk: usize = items.iter().map(|i| i.total_bulk()).sum();
+
+        if self.load() + weight > self.carry_limit() {
+            return Err(TCError::user(
+                "That's too heavy! You'd need to drop something first.",
+            ));
+        }
+
+        if self.inventory.bulk() + bulk > MAX_BULK {
+            return Err(TCError::user(
+                "Your hands are full. Maybe put something in a bag?",
+            ));
+        }
+
+        Ok(())
     }
 
     pub fn is_alive(&self) -> bool {
@@ -135,13 +471,50 @@ impl Mob {
         !self.is_alive()
     }
 
-    pub fn fight(&self, mobs: &[Mob], _dice: &mut Dice) -> Vec<Action> {
+    /// Returns true if this Mob is hurt badly enough to want to run
+    pub fn is_wimpy(&self) -> bool {
+        self.wimpy > 0 && self.is_alive() && self.health() < self.wimpy
+    }
+
+    /// Leaves behind a corpse holding everything this Mob carried and wore (and their money). Heroes become
+    /// ghosts and lose some of their experience; everyone else is gone for good.
+    pub fn die(&mut self, decays_at: u64) -> Item {
+        let corpse = Item::corpse(self, decays_at);
+
+        self.hp = 0;
+        self.inventory = Inventory::new();
+        self.equipment = Equipment::new();
+        self.wallet = Wallet::new();
+        self.enemies.clear();
+        self.statuses.clear();
+        self.following = None;
+
+        if self.is_hero() {
+            self.ghost = true;
+            self.experience -= self.experience * DEATH_PENALTY / 100;
+        }
+
+        corpse
+    }
+
+    /// Brings a ghost back to life with half of their health
+    pub fn respawn(&mut self) {
+        self.ghost = false;
+        self.hp = std::cmp::max(self.max_health() / 2, 1);
+    }
+
+    pub fn fight(&self, mobs: &[Mob], dice: &mut Dice) -> Vec<Action> {
         // no enemies? no worries
         if self.enemies.is_empty() {
             trace!("{} won't fight - no enemies!", self.name());
             return vec![];
         }
 
+        if self.statuses.has(Condition::Stunned) {
+            trace!("{} can't fight - stunned!", self.name());
+            return vec![];
+        }
+
         // see if any of the mobs are enemies.
         let enemies: Vec<Mob> = mobs
             .iter()
@@ -159,16 +532,30 @@ impl Mob {
             }
         };
 
-        // TODO: determine different kinds of attacks
-        // TODO: multiple attacks
         // TODO: healing actions
-        let attack = Action::new(
-            &self.identifier,
-            &target.identifier,
-            Effect::Harm(Damage::Blunt(1)),
-        );
+        let unarmed = [combat::unarmed()];
+        let attacks = match self.equipment.weapon() {
+            Some(weapon) if !weapon.attacks.is_empty() => &weapon.attacks[..],
+            _ if self.attacks.is_empty() => &unarmed[..],
+            _ => &self.attacks[..],
+        };
+
+        let rounds = std::cmp::max(self.attacks_per_round, 1);
+
+        let mut output = vec![];
+
+        for _ in 0..rounds {
+            if let Some(attack) = combat::choose(attacks, dice) {
+                output.push(Action::attack(
+                    &self.identifier,
+                    &target.identifier,
+                    &attack.name,
+                    combat::resolve(self, target, attack, dice),
+                ));
+            }
+        }
 
-        vec![attack]
+        output
     }
 
     pub fn act(&mut self, action: Action, world: &World) -> Vec<Update> {
@@ -184,40 +571,220 @@ impl Mob {
             }
         };
 
+        // the dead don't get a parting shot
+        if from_mob.is_dead() {
+            return vec![];
+        }
+
+        let attack = action.attack.as_deref();
+
         match action.effect {
-            Effect::Harm(damage) => self.harm(&from_mob, damage, world),
-            Effect::Heal(_restore) => vec![],
+            Effect::Harm(damage) => self.harm(&from_mob, attack, damage, false, world),
+            Effect::Critical(damage) => self.harm(&from_mob, attack, damage, true, world),
+            Effect::Miss => self.dodge(&from_mob, attack),
+            Effect::Heal(restore) => self.heal(Some(&from_mob), restore, world),
         }
     }
 
-    fn harm(&mut self, attacker: &Mob, damage: Damage, _world: &World) -> Vec<Update> {
-        if damage.health() >= self.hp {
+    fn harm(
+        &mut self,
+        attacker: &Mob,
+        attack: Option<&str>,
+        damage: Damage,
+        critical: bool,
+        _world: &World,
+    ) -> Vec<Update> {
+        let resistance = self.resistance(damage.kind());
+        let resisted = match resistance {
+            Some(r) => r.apply(damage.health()),
+            None => damage.health(),
+        };
+
+        // armour soaks up some of every hit, but can't stop it entirely
+        let hp = match resisted {
+            0 => 0,
+            _ => std::cmp::max(resisted.saturating_sub(self.equipment.armour()), 1),
+        };
+
+        if hp >= self.hp {
             self.hp = 0;
         } else {
-            self.hp -= damage.health();
+            self.hp -= hp;
         }
 
-        let target_update =
-            Update::combat(&self.identifier, format!("{} hurt you!", attacker.name()));
+        let target_message = match (attack, critical) {
+            (Some(a), true) => format!("{}'s {} lands a crushing blow on you!", attacker.name(), a),
+            (Some(a), false) => format!("{}'s {} hurts you!", attacker.name(), a),
+            (None, true) => format!("{} lands a crushing blow on you!", attacker.name()),
+            (None, false) => format!("{} hurt you!", attacker.name()),
+        };
 
-        let attacker_update = if self.is_dead() {
-            Update::combat(&attacker.identifier, format!("You killed {}!", self.name()))
-        } else {
-            Update::combat(&attacker.identifier, format!("You hit {}!", self.name()))
+        let attacker_message = match (attack, critical) {
+            _ if self.is_dead() => format!("You killed {}!", self.name()),
+            (Some(a), true) => format!("Your {} lands a crushing blow on {}!", a, self.name()),
+            (Some(a), false) => format!("Your {} hits {}!", a, self.name()),
+            (None, true) => format!("You land a crushing blow on {}!", self.name()),
+            (None, false) => format!("You hit {}!", self.name()),
         };
 
-        vec![target_update, attacker_update]
+        let mut output = vec![
+            Update::combat(&self.identifier, target_message),
+            Update::combat(&attacker.identifier, attacker_message),
+        ];
+
+        if let Some(r) = resistance {
+            let (target_message, attacker_message) = match r {
+                Resistance::Immune => (
+                    "You seem unaffected.".to_owned(),
+                    format!("{} seems unaffected.", self.name()),
+                ),
+                Resistance::Resistant => (
+                    "You shrug off the worst of it.".to_owned(),
+                    format!("{} shrugs off the worst of it.", self.name()),
+                ),
+                Resistance::Vulnerable => {
+                    let message = damage.kind().vulnerable_message().to_owned();
+                    (message.clone(), message)
+                }
+            };
+
+            output.push(Update::combat(&self.identifier, target_message));
+            output.push(Update::combat(&attacker.identifier, attacker_message));
+        }
+
+        if hp > 0 && self.is_alive() {
+            if let Some(status) = Status::from_damage(damage.kind(), critical) {
+                output.append(&mut self.afflict(Some(attacker), status));
+            }
+
+            // armour that takes a beating wears out
+            let broken = self.equipment.wear_armour();
+            if !broken.is_empty() {
+                output.extend(broken.iter().map(|name| {
+                    Update::combat(&self.identifier, format!("Your {} falls apart!", name))
+                }));
+                output.push(Update::equipment(&self.identifier, &self.equipment));
+            }
+        }
+
+        output
+    }
+
+    /// Wears down the weapon in hand after it lands a blow
+    pub fn wear_weapon(&mut self) -> Vec<Update> {
+        match self.equipment.wear_weapon() {
+            Some(name) => vec![
+                Update::combat(&self.identifier, format!("Your {} breaks!", name)),
+                Update::equipment(&self.identifier, &self.equipment),
+            ],
+            None => vec![],
+        }
+    }
+
+    /// Applies a status to this Mob, following the stacking rules for its condition
+    pub fn afflict(&mut self, source: Option<&Mob>, status: Status) -> Vec<Update> {
+        let condition = status.condition;
+        let mut output = vec![];
+
+        if self.statuses.add(condition, status.ticks, status.potency) {
+            output.push(Update::combat(
+                &self.identifier,
+                condition.onset_message().to_owned(),
+            ));
+
+            if let Some(source) = source {
+                output.push(Update::combat(
+                    &source.identifier,
+                    format!("{} is {}!", self.name(), condition.name()),
+                ));
+            }
+        }
+
+        output.push(Update::status(&self.identifier, &self.statuses));
+        output
+    }
+
+    fn dodge(&self, attacker: &Mob, attack: Option<&str>) -> Vec<Update> {
+        let (target_message, attacker_message) = match attack {
+            Some(a) => (
+                format!("{}'s {} misses you.", attacker.name(), a),
+                format!("Your {} misses {}.", a, self.name()),
+            ),
+            None => (
+                format!("{} misses you.", attacker.name()),
+                format!("You miss {}.", self.name()),
+            ),
+        };
+
+        vec![
+            Update::combat(&self.identifier, target_message),
+            Update::combat(&attacker.identifier, attacker_message),
+        ]
     }
 
     fn heal(&mut self, _healer: Option<&Mob>, restore: Restore, _world: &World) -> Vec<Update> {
-        let new_hp = self.hp + restore.health();
-        if new_hp > self.constitution {
-            self.hp = self.constitution;
+        let new_hp = self.hp.saturating_add(restore.health());
+        if new_hp > self.max_health() {
+            self.hp = self.max_health();
         } else {
             self.hp = new_hp;
         }
 
-        vec![Update::health(&self.identifier, self.health())]
+        let mut output = vec![Update::health(&self.identifier, self.health())];
+
+        let cured = match restore {
+            Restore::Max => self.statuses.clear(),
+            Restore::Antidote(_) if self.statuses.cure(Condition::Poisoned) => {
+                vec![Condition::Poisoned]
+            }
+            _ => vec![],
+        };
+
+        output.append(&mut self.expire(cured));
+        output
+    }
+
+    /// Tells this Mob about conditions that have worn off or been cured
+    fn expire(&self, conditions: Vec<Condition>) -> Vec<Update> {
+        if conditions.is_empty() {
+            return vec![];
+        }
+
+        let mut output: Vec<Update> = conditions
+            .iter()
+            .map(|c| Update::info(&self.identifier, c.expiry_message()))
+            .collect();
+
+        output.push(Update::status(&self.identifier, &self.statuses));
+        output
+    }
+
+    /// Suffers damage from poison and burning, which can kill. The World buries
+    /// those it kills once the caches are unlocked (see `World::tick`).
+    fn suffer(&mut self) -> Vec<Update> {
+        let poison = self.statuses.potency(Condition::Poisoned);
+        let burning = self.statuses.potency(Condition::Burning);
+
+        let hp = std::cmp::min(poison + burning, self.hp);
+        if hp == 0 {
+            return vec![];
+        }
+
+        self.hp -= hp;
+
+        let mut output = vec![];
+        if poison > 0 {
+            output.push(Update::combat(
+                &self.identifier,
+                "You feel the poison in your veins.".to_owned(),
+            ));
+        }
+        if burning > 0 {
+            output.push(Update::combat(&self.identifier, "You burn!".to_owned()));
+        }
+
+        output.push(Update::health(&self.identifier, self.health()));
+        output
     }
 }
 
@@ -234,14 +801,46 @@ impl Entity for Mob {
 }
 
 impl Tickable for Mob {
-    fn tick(&mut self, world: &World, _dice: &mut Dice) -> Vec<Update> {
+    fn tick(&mut self, world: &World, dice: &mut Dice) -> Vec<Update> {
         let mut output = vec![];
 
-        // default healing per tick
-        output.append(&mut self.heal(None, Restore::Health(1), world));
+        // ghosts don't suffer or heal until they respawn
+        if !self.ghost {
+            // lingering damage, then default healing per tick (unless poisoned)
+            output.append(&mut self.suffer());
+            if self.is_dead() {
+                return output;
+            }
+
+            if !self.statuses.has(Condition::Poisoned) {
+                output.append(&mut self.heal(None, Restore::Health(1), world));
+            }
+
+            let expired = self.statuses.tick();
+            output.append(&mut self.expire(expired));
+        }
+
+        // crafting is done once the mob is no longer busy
+        if let Doing::Crafting(name) = &self.doing {
+            if !self.is_busy() {
+                let name = name.clone();
+                self.doing = Doing::Nothing;
+                output.append(&mut self.craft(&name, world, dice));
+            }
+        }
+
+        // shopkeepers restock their wares the way spaces spawn items
+        if self.is_alive() {
+            self.restock(world, dice);
+        }
+
+        // nocturnal mobs lose interest in fighting once night is over
+        if self.behaviour.nocturnal && !self.enemies.is_empty() && !world.clock().is_night() {
+            self.enemies.clear();
+        }
 
         // time transition? hero? let the player know
-        if self.prototype.eq("HERO") {
+        if self.is_hero() {
             for transition in world.clock().transition() {
                 match transition {
                     Transition::Morning => output.push(Update::transition(
@@ -313,11 +912,94 @@ pub struct Description {
     pub text: String,
     pub clicks: HashMap<String, String>,
 }
+/// Flags that drive how an NPC acts without being told to
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Behaviour {
+    /// Attacks heroes on sight
+    pub aggressive: bool,
+
+    /// Only starts (or joins) fights at night
+    pub nocturnal: bool,
+
+    /// Joins fights that other Mobs of the same prototype are in
+    pub assist: bool,
+}
+
+/// How an NPC moves around the world without being told to
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Movement {
+    /// The chance (in percent) of wandering through a random exit each tick
+    pub wander: usize,
+
+    /// Spaces to walk between in order, looping back to the start
+    pub patrol: Vec<Identifier>,
+
+    /// Only wander into spaces tagged with this area
+    pub area: Option<String>,
+
+    /// Heads back to where it spawned when it isn't wandering or patrolling
+    pub home: bool,
+}
+
+/// How weighed down a Mob is by everything they carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Encumbrance {
+    /// Carrying up to half of their limit
+    Unburdened,
+
+    /// Carrying up to three quarters of their limit: slower to move, and a little clumsy
+    Burdened,
+
+    /// Carrying more than that: much slower to move, and much clumsier
+    Stressed,
+}
+
+impl Encumbrance {
+    pub fn from_load(load: usize, limit: usize) -> Self {
+        if load * 2 <= limit {
+            Self::Unburdened
+        } else if load * 4 <= limit * 3 {
+            Self::Burdened
+        } else {
+            Self::Stressed
+        }
+    }
+
+    /// How long the Mob needs to catch their breath after moving
+    pub fn delay(self) -> Option<Duration> {
+        match self {
+            Self::Unburdened => None,
+            Self::Burdened => Some(Duration::from_secs(1)),
+            Self::Stressed => Some(Duration::from_secs(3)),
+        }
+    }
+
+    /// Taken off attack and flee rolls
+    pub fn penalty(self) -> isize {
+        match self {
+            Self::Unburdened => 0,
+            Self::Burdened => 1,
+            Self::Stressed => 3,
+        }
+    }
+
+    /// What the Mob sees when their load changes to this level
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::Unburdened => "Your load feels lighter.",
+            Self::Burdened => "You're burdened by your load.",
+            Self::Stressed => "You strain under your load!",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum Doing {
     Nothing,
     Casting,
     Fighting,
+    Moving,
+    Crafting(String),
 }
 
 impl Default for Doing {
@@ -336,6 +1018,16 @@ pub enum Damage {
 }
 
 impl Damage {
+    pub fn kind(&self) -> DamageKind {
+        match self {
+            Self::Blunt(_) => DamageKind::Blunt,
+            Self::Edged(_) => DamageKind::Edged,
+            Self::Pointed(_) => DamageKind::Pointed,
+            Self::Poison(_) => DamageKind::Poison,
+            Self::Fire(_) => DamageKind::Fire,
+        }
+    }
+
     pub fn health(&self) -> usize {
         match self {
             Self::Blunt(hp) => *hp,
@@ -347,6 +1039,52 @@ impl Damage {
     }
 }
 
+/// The kinds of Damage, without an amount (eg: as declared by an attack in a template)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DamageKind {
+    Blunt,
+    Edged,
+    Pointed,
+    Poison,
+    Fire,
+}
+
+impl DamageKind {
+    /// Maps the names builders use in templates (eg: "piercing") onto a kind of damage
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_ref() {
+            "blunt" | "bludgeoning" | "crushing" => Some(Self::Blunt),
+            "edged" | "slashing" | "cutting" => Some(Self::Edged),
+            "pointed" | "piercing" | "stabbing" => Some(Self::Pointed),
+            "poison" => Some(Self::Poison),
+            "fire" | "burning" => Some(Self::Fire),
+            _ => None,
+        }
+    }
+
+    /// What a Mob vulnerable to this kind of damage experiences
+    pub fn vulnerable_message(self) -> &'static str {
+        match self {
+            Self::Blunt => "It crunches!",
+            Self::Edged => "It cuts deep!",
+            Self::Pointed => "It pierces deep!",
+            Self::Poison => "It festers!",
+            Self::Fire => "It burns!",
+        }
+    }
+
+    pub fn damage(self, hp: usize) -> Damage {
+        match self {
+            Self::Blunt => Damage::Blunt(hp),
+            Self::Edged => Damage::Edged(hp),
+            Self::Pointed => Damage::Pointed(hp),
+            Self::Poison => Damage::Poison(hp),
+            Self::Fire => Damage::Fire(hp),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Restore {
     Max,
@@ -369,6 +1107,8 @@ pub struct Action {
     pub from: Identifier,
     pub to: Identifier,
     pub effect: Effect,
+    /// The name of the attack that caused this action, if any (eg: "bite")
+    pub attack: Option<String>,
 }
 
 impl Action {
@@ -377,6 +1117,14 @@ impl Action {
             from: from.clone(),
             to: to.clone(),
             effect,
+            attack: None,
+        }
+    }
+
+    pub fn attack(from: &Identifier, to: &Identifier, attack: &str, effect: Effect) -> Self {
+        Self {
+            attack: Some(attack.to_owned()),
+            ..Self::new(from, to, effect)
         }
     }
 }
@@ -384,5 +1132,52 @@ impl Action {
 #[derive(Debug)]
 pub enum Effect {
     Harm(Damage),
+    Critical(Damage),
+    Miss,
     Heal(Restore),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thing(weight: usize, bulk: usize) -> Item {
+        let mut item = Item::new();
+        item.name = "thing".to_owned();
+        item.weight = weight;
+        item.bulk = bulk;
+        item
+    }
+
+    #[test]
+    fn test_encumbrance() {
+        assert_eq!(Encumbrance::from_load(50, 100), Encumbrance::Unburdened);
+        assert_eq!(Encumbrance::from_load(75, 100), Encumbrance::Burdened);
+        assert_eq!(Encumbrance::from_load(76, 100), Encumbrance::Stressed);
+
+        let mut mob = Mob::new();
+        mob.strength = 10;
+        assert_eq!(mob.carry_limit(), 10 * CARRY_PER_STRENGTH);
+        mob.inventory.add(thing(80, 1));
+        assert_eq!(mob.encumbrance(), Encumbrance::Stressed);
+        assert!(mob.encumbrance().delay().is_some());
+    }
+
+    #[test]
+    fn test_can_carry() {
+        let mut mob = Mob::new();
+        mob.strength = 10;
+
+        assert!(mob.can_carry(&[thing(100, 1)]).is_ok());
+        assert!(mob.can_carry(&[thing(101, 1)]).is_err());
+        assert!(mob.can_carry(&[thing(1, MAX_BULK + 1)]).is_err());
+
+        // a bag full of things is as bulky as what's in it
+        let mut bag = thing(1, 1);
+        bag.capacity = MAX_BULK;
+        for _ in 0..MAX_BULK {
+            bag.contents.add(thing(0, 1));
+        }
+        assert!(mob.can_carry(&[bag]).is_err());
+    }
+}
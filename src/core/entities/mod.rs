@@ -2,7 +2,9 @@ pub mod cache;
 pub mod mob;
 pub mod space;
 
-pub use mob::{Action, Doing, Mob, Restore};
+pub use mob::{
+    Action, Behaviour, Damage, DamageKind, Doing, Effect, Encumbrance, Mob, Movement, Restore,
+};
 pub use space::Space;
 
 use crate::core::{Dice, Identifier, Markup, TCError, Update, World};
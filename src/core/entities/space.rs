@@ -3,9 +3,19 @@ use crate::core::*;
 
 use std::collections::HashMap;
 
+/// How long (in ticks) an NPC's corpse lies around before crumbling away
+const NPC_CORPSE_DECAY: u64 = 60;
+
+/// Heroes get longer to come back for their belongings
+const HERO_CORPSE_DECAY: u64 = 720;
+
 #[derive(Clone, Debug)]
 pub struct Space {
     identifier: Identifier,
+    /// Where heroes who die here respawn, if not at the origin
+    pub recall: Option<Identifier>,
+    /// A tag shared by neighbouring spaces, to keep wandering mobs in their part of the world
+    pub area: Option<String>,
     pub description: Description,
     pub exits: HashMap<String, Identifier>,
     pub population: Population,
@@ -18,6 +28,8 @@ impl Space {
     pub fn new(identifier: &Identifier) -> Self {
         Self {
             identifier: identifier.to_owned(),
+            recall: None,
+            area: None,
             description: Description::default(),
             exits: HashMap::new(),
             population: Population::default(),
@@ -34,6 +46,97 @@ impl Space {
             .collect()
     }
 
+    /// Sends a message to everyone in the space, except for the listed mobs
+    pub fn announce(&self, except: &[&Identifier], message: &str) -> Vec<Update> {
+        self.population()
+            .iter()
+            .filter(|id| !except.contains(id))
+            .map(|id| Update::info(id, message))
+            .collect()
+    }
+
+    /// Where heroes who die in this space respawn
+    pub fn recall(&self) -> Identifier {
+        self.recall.clone().unwrap_or_else(Identifier::origin)
+    }
+
+    /// Lets NPCs pick fights on their own: aggressive mobs attack heroes on sight,
+    /// and mobs that assist join fights their own kind are already in.
+    fn stir(&self, world: &World) -> Vec<Update> {
+        let mut output = vec![];
+
+        let locals: Vec<Mob> = self
+            .population()
+            .iter()
+            .flat_map(|id| world.mobs.get(id))
+            .filter(|m| m.is_alive())
+            .collect();
+
+        for id in self.population() {
+            let mut mob = match world.mobs.get(id) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+
+            if mob.is_hero() || mob.is_dead() || mob.is_busy() {
+                continue;
+            }
+
+            if mob.behaviour.nocturnal && !world.clock().is_night() {
+                continue;
+            }
+
+            let mut targets: Vec<Identifier> = vec![];
+
+            if mob.behaviour.aggressive {
+                targets.extend(
+                    locals
+                        .iter()
+                        .filter(|m| m.is_hero())
+                        .map(|m| m.identifier().clone()),
+                );
+            }
+
+            if mob.behaviour.assist {
+                for ally in locals
+                    .iter()
+                    .filter(|m| m.prototype == mob.prototype && m.identifier() != id)
+                {
+                    targets.extend(
+                        locals
+                            .iter()
+                            .filter(|m| ally.enemies.contains(m.identifier()))
+                            .filter(|m| m.prototype != mob.prototype)
+                            .map(|m| m.identifier().clone()),
+                    );
+                }
+            }
+
+            targets.sort();
+            targets.dedup();
+            targets.retain(|t| !mob.enemies.contains(t));
+
+            for target_id in targets {
+                if let Ok(mut target) = world.mobs.get(&target_id) {
+                    world.provoke(&mut mob, &mut target);
+                    output.push(Update::combat(
+                        &target_id,
+                        format!("{} attacks you!", mob.name()),
+                    ));
+                    output.append(&mut self.announce(
+                        &[id, &target_id],
+                        &format!("{} attacks {}!", mob.name(), target.name()),
+                    ));
+                    world.mobs.insert(target);
+                }
+            }
+
+            world.mobs.insert(mob);
+        }
+
+        output
+    }
+
     pub fn space_update(&self, world: &World) -> Vec<Update> {
         self.population()
             .iter()
@@ -50,38 +153,55 @@ impl Entity for Space {
 
 impl Tickable for Space {
     fn tick(&mut self, world: &World, dice: &mut Dice) -> Vec<Update> {
+        let mut output = vec![];
+
+        for item in self.inventory.remove_decayed(world.clock().tick) {
+            output
+                .append(&mut self.announce(&[], &format!("The {} crumbles to dust.", item.name())));
+        }
+
         for s in self.item_spawn.iter() {
             // based on the chance of spawning
             if s.should_spawn(dice) {
                 // do we already have the maximum inventory of this item?
                 if self.inventory.count(&s.name) < s.max {
-                    if let Some(item) = world.item_prototypes.create(&s.name) {
+                    if let Some(item) = world.item_prototypes.create(&s.name, dice) {
                         self.inventory.add(item);
                     }
                 }
             }
         }
 
+        // count the mobs that call this space home, even if they've wandered off
         let mut mob_counter: HashMap<String, usize> = HashMap::new();
-        for p in self.population.prototypes(&world) {
-            let count = mob_counter.entry(p).or_insert(0);
+        for m in world
+            .mobs
+            .select(|m| m.home.as_ref() == Some(self.identifier()))
+        {
+            let count = mob_counter.entry(m.prototype).or_insert(0);
             *count += 1;
         }
 
+        let mut spawned = vec![];
+
         for s in self.mob_spawn.iter() {
             if s.should_spawn(dice) {
                 // do we already have the maximum population of this mob?
                 if mob_counter.get(&s.name).unwrap_or(&0) < &s.max {
-                    if let Some(mut mob) = world.mob_prototypes.create(&s.name) {
+                    if let Some(mut mob) = world.mob_prototypes.create(&s.name, dice) {
                         mob.space_id = self.identifier().clone();
+                        mob.home = Some(self.identifier().clone());
                         self.population.add(mob.identifier());
+                        spawned.push((mob.identifier().clone(), mob.name().to_owned()));
                         world.mobs.insert(mob);
                     }
                 }
             }
         }
 
-        let mut output = vec![];
+        for (id, name) in spawned {
+            output.append(&mut self.announce(&[&id], &format!("{} appears.", name)));
+        }
 
         output.append(&mut self.population_update(world));
         output.append(&mut self.space_update(world));
@@ -101,7 +221,7 @@ impl Describe for Space {
 
             self.inventory.items().iter().for_each(|i| {
                 let count = item_counts.entry(i.name().to_owned()).or_insert(0);
-                *count += 1;
+                *count += i.quantity;
             });
 
             // use the count to create descriptive slugs for each kind of item
@@ -117,7 +237,8 @@ impl Describe for Space {
                 item_slugs.push(slug);
 
                 // make the items clickable
-                clicks.insert(name.clone(), format!("take {}", name));
+                let verb = if name == "corpse" { "loot" } else { "take" };
+                clicks.insert(name.clone(), format!("{} {}", verb, name));
             }
 
             text += &format!("\n\nYou see {} here.", item_slugs.join(", "));
@@ -127,13 +248,62 @@ impl Describe for Space {
     }
 }
 
+impl Space {
+    /// Handles a death here: the dead leave a corpse, heroes linger as ghosts, and
+    /// everyone else is removed. The killer (if there is one) is rewarded.
+    pub fn bury(
+        &mut self,
+        mut target: Mob,
+        killer: Option<&Identifier>,
+        world: &World,
+    ) -> Vec<Update> {
+        let mut updates = vec![Update::combat(
+            target.identifier(),
+            "You have been killed!".to_owned(),
+        )];
+
+        let mut quiet = vec![target.identifier()];
+        quiet.extend(killer);
+        updates.append(&mut self.announce(&quiet, &format!("{} dies.", target.name())));
+
+        if let Some(killer) = killer {
+            updates.append(&mut world.reward(killer, &target));
+        }
+
+        let decay = if target.is_hero() {
+            HERO_CORPSE_DECAY
+        } else {
+            NPC_CORPSE_DECAY
+        };
+        self.inventory.add(target.die(world.clock().tick + decay));
+
+        if target.is_hero() {
+            updates.push(Update::info(
+                target.identifier(),
+                "You are now a ghost. RESPAWN when you're ready to return to life.",
+            ));
+            updates.push(Update::inventory(target.identifier(), &target.inventory));
+            updates.push(Update::status(target.identifier(), &target.statuses));
+            world.mobs.insert(target);
+        } else {
+            self.population.remove(target.identifier());
+            world.mobs.remove(target.identifier());
+        }
+
+        updates.append(&mut self.space_update(world));
+        updates
+    }
+}
+
 impl Melee for Space {
     fn population(&self) -> &[Identifier] {
         self.population.identifiers()
     }
 
     fn melee(&mut self, world: &World, dice: &mut Dice) -> Vec<Update> {
-        let population_count = self.population().len();
+        let mut deaths = false;
+
+        let mut updates = self.stir(world);
 
         // filter out mobs that are busy or have no enemies
         let mobs: Vec<Mob> = self
@@ -146,31 +316,42 @@ impl Melee for Space {
         // get the list of attacks from the mobs
         let actions: Vec<Action> = mobs.iter().flat_map(|m| m.fight(&mobs, dice)).collect();
 
-        let mut updates = vec![];
-
         // apply the actions to the mobs
         for action in actions {
             if let Ok(mut target) = world.mobs.get(&action.to) {
+                // an earlier blow this round may have finished them off
+                if target.is_dead() {
+                    continue;
+                }
+
                 trace!("Applying action ... {:?}!", action);
+                let action_from = action.from.clone();
+                let landed = matches!(action.effect, Effect::Harm(_) | Effect::Critical(_));
                 updates.append(&mut target.act(action, world));
                 updates.push(Update::health(target.identifier(), target.health()));
 
-                // remove the dead from the population and the world
                 if target.is_dead() {
-                    updates.push(Update::combat(
-                        target.identifier(),
-                        "You have been killed!".to_owned(),
-                    ));
-                    self.population.remove(target.identifier());
-                    world.mobs.remove(target.identifier());
+                    deaths = true;
+                    updates.append(&mut self.bury(target, Some(&action_from), world));
                 } else {
-                    // mob is still alive, so update the target mob
+                    // mob is still alive, so update the target mob (and see if they panic)
+                    if target.is_wimpy() {
+                        target.fleeing = true;
+                    }
                     world.mobs.insert(target);
                 }
+
+                // weapons wear out with every blow that lands
+                if landed {
+                    if let Ok(mut attacker) = world.mobs.get(&action_from) {
+                        updates.append(&mut attacker.wear_weapon());
+                        world.mobs.insert(attacker);
+                    }
+                }
             }
         }
 
-        if population_count != self.population().len() {
+        if deaths {
             updates.append(&mut self.population_update(world));
         }
 
@@ -0,0 +1,257 @@
+use crate::core::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Where on a Mob an Item can be worn (or wielded)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Slot {
+    Head,
+    Neck,
+    Body,
+    Hands,
+    Finger,
+    Feet,
+    Shield,
+    Wield,
+}
+
+impl Slot {
+    /// Maps the names builders use in templates (eg: "weapon") onto a slot
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_ref() {
+            "head" | "helmet" => Some(Self::Head),
+            "neck" | "amulet" => Some(Self::Neck),
+            "body" | "armour" | "armor" | "torso" => Some(Self::Body),
+            "hands" | "gloves" => Some(Self::Hands),
+            "finger" | "ring" => Some(Self::Finger),
+            "feet" | "boots" => Some(Self::Feet),
+            "shield" | "offhand" => Some(Self::Shield),
+            "wield" | "weapon" => Some(Self::Wield),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Head => "head",
+            Self::Neck => "neck",
+            Self::Body => "body",
+            Self::Hands => "hands",
+            Self::Finger => "finger",
+            Self::Feet => "feet",
+            Self::Shield => "shield",
+            Self::Wield => "wielded",
+        }
+    }
+}
+
+/// The attributes an equipped Item can raise (or lower)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Attribute {
+    Strength,
+    Constitution,
+    Dexterity,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl Attribute {
+    /// Maps the names builders use in templates (eg: "str") onto an attribute
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_ref() {
+            "strength" | "str" => Some(Self::Strength),
+            "constitution" | "con" => Some(Self::Constitution),
+            "dexterity" | "dex" => Some(Self::Dexterity),
+            "intelligence" | "int" => Some(Self::Intelligence),
+            "wisdom" | "wis" => Some(Self::Wisdom),
+            "charisma" | "cha" => Some(Self::Charisma),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Strength => "strength",
+            Self::Constitution => "constitution",
+            Self::Dexterity => "dexterity",
+            Self::Intelligence => "intelligence",
+            Self::Wisdom => "wisdom",
+            Self::Charisma => "charisma",
+        }
+    }
+}
+
+/// The Items a Mob is wearing or wielding, at most one per slot. Kept apart
+/// from the Inventory, and stored as a list since records can't be keyed by slot.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Equipment {
+    items: Vec<Item>,
+}
+
+impl Equipment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Equipped items, in slot order
+    pub fn items(&self) -> &Vec<Item> {
+        &self.items
+    }
+
+    pub fn get(&self, slot: Slot) -> Option<&Item> {
+        self.items.iter().find(|i| i.slot == Some(slot))
+    }
+
+    /// Equips an Item in its slot, returning whatever was there before. Items
+    /// without a slot are handed straight back.
+    pub fn equip(&mut self, item: Item) -> Option<Item> {
+        let slot = match item.slot {
+            Some(s) => s,
+            None => return Some(item),
+        };
+
+        let previous = self.unequip(slot);
+        self.items.push(item);
+        self.items.sort_by_key(|i| i.slot);
+
+        previous
+    }
+
+    /// Takes the Item out of a slot
+    pub fn unequip(&mut self, slot: Slot) -> Option<Item> {
+        let idx = self.items.iter().position(|i| i.slot == Some(slot))?;
+        Some(self.items.remove(idx))
+    }
+
+    /// Takes off the Item with the given name
+    pub fn remove(&mut self, name: &str) -> Option<Item> {
+        let idx = self.items.iter().position(|i| i.name() == name)?;
+        Some(self.items.remove(idx))
+    }
+
+    /// Removes everything, returning it
+    pub fn drain(&mut self) -> Vec<Item> {
+        self.items.drain(..).collect()
+    }
+
+    /// The weapon in hand, if any (and if it isn't broken)
+    pub fn weapon(&self) -> Option<&Item> {
+        self.get(Slot::Wield).filter(|i| !i.is_broken())
+    }
+
+    /// Equipped items that still work
+    fn working(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter().filter(|i| !i.is_broken())
+    }
+
+    /// How much damage the equipped Items soak up from each hit. Enchantments
+    /// on anything but the weapon add to it.
+    pub fn armour(&self) -> usize {
+        self.working()
+            .filter(|i| i.slot != Some(Slot::Wield))
+            .map(|i| i.armour + i.enchantment)
+            .sum()
+    }
+
+    /// The total change to an attribute from everything equipped
+    pub fn bonus(&self, attribute: Attribute) -> isize {
+        self.working()
+            .flat_map(|i| i.all_bonuses())
+            .filter(|(a, _)| *a == attribute)
+            .map(|(_, b)| *b)
+            .sum()
+    }
+
+    /// Wears down the weapon after it lands a blow, returning its name if it broke
+    pub fn wear_weapon(&mut self) -> Option<String> {
+        let weapon = self
+            .items
+            .iter_mut()
+            .find(|i| i.slot == Some(Slot::Wield))?;
+
+        if weapon.wear() {
+            Some(weapon.name().to_owned())
+        } else {
+            None
+        }
+    }
+
+    /// Wears down the armour after a blow gets through, returning anything that broke
+    pub fn wear_armour(&mut self) -> Vec<String> {
+        self.items
+            .iter_mut()
+            .filter(|i| i.slot != Some(Slot::Wield) && i.armour + i.enchantment > 0)
+            .filter_map(|i| {
+                if i.wear() {
+                    Some(i.name().to_owned())
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Describes what's equipped for the client (eg: "head: helmet")
+    pub fn describe(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter_map(|i| {
+                let broken = if i.is_broken() { " (broken)" } else { "" };
+                i.slot
+                    .map(|s| format!("{}: {}{}", s.name(), i.name(), broken))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, slot: Slot) -> Item {
+        let mut item = Item::new();
+        item.name = name.to_owned();
+        item.slot = Some(slot);
+        item
+    }
+
+    #[test]
+    fn test_one_item_per_slot() {
+        let mut equipment = Equipment::new();
+
+        let mut helmet = item("helmet", Slot::Head);
+        helmet.armour = 1;
+        helmet.bonuses = vec![(Attribute::Wisdom, -1)];
+
+        let mut crown = item("crown", Slot::Head);
+        crown.bonuses = vec![(Attribute::Charisma, 2), (Attribute::Wisdom, 1)];
+
+        assert!(equipment.equip(item("stick", Slot::Wield)).is_none());
+        assert!(equipment.equip(helmet).is_none());
+        assert_eq!(equipment.armour(), 1);
+        assert_eq!(equipment.bonus(Attribute::Wisdom), -1);
+
+        // swapping hats hands back the old one
+        let swapped = equipment.equip(crown).unwrap();
+        assert_eq!(swapped.name(), "helmet");
+        assert_eq!(equipment.armour(), 0);
+        assert_eq!(equipment.bonus(Attribute::Wisdom), 1);
+
+        assert_eq!(
+            equipment.describe(),
+            vec!["head: crown".to_owned(), "wielded: stick".to_owned()]
+        );
+
+        assert_eq!(equipment.weapon().unwrap().name(), "stick");
+        assert!(equipment.remove("stick").is_some());
+        assert!(equipment.weapon().is_none());
+        assert!(equipment.equip(Item::new()).is_some());
+    }
+}
@@ -2,6 +2,34 @@ use crate::core::*;
 
 use serde::{Deserialize, Serialize};
 
+/// How many of something a command is after (eg: `TAKE 3 rocks`, `DROP all rocks`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quantity {
+    One,
+    Count(usize),
+    All,
+}
+
+impl Quantity {
+    /// Splits a leading count (or "all") off the arguments, leaving the rest
+    pub fn parse(args: &[String]) -> (Self, &[String]) {
+        match args.split_first() {
+            Some((first, rest)) if !rest.is_empty() => {
+                if first.eq_ignore_ascii_case("all") {
+                    (Self::All, rest)
+                } else if let Ok(count) = first.parse::<usize>() {
+                    (Self::Count(count), rest)
+                } else {
+                    (Self::One, args)
+                }
+            }
+            _ => (Self::One, args),
+        }
+    }
+}
+
+/// Items carried by a Mob or lying in a Space, sorted by name. Stackable items
+/// of the same prototype share a single entry with a quantity.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Inventory {
     items: Vec<Item>,
@@ -33,19 +61,191 @@ impl Inventory {
         false
     }
 
-    pub fn count(&self, name: &str) -> usize {
-        self.items().iter().filter(|i| i.prototype == name).count()
+    pub fn weight(&self) -> usize {
+        self.items.iter().map(|i| i.total_weight()).sum()
+    }
+
+    pub fn bulk(&self) -> usize {
+        self.items.iter().map(|i| i.total_bulk()).sum()
+    }
+
+    /// How many items there are, counting every item in a stack
+    pub fn quantity(&self) -> usize {
+        self.items.iter().map(|i| i.quantity).sum()
+    }
+
+    /// How many items of a prototype there are, counting every item in a stack
+    pub fn count(&self, prototype: &str) -> usize {
+        self.items()
+            .iter()
+            .filter(|i| i.prototype == prototype)
+            .map(|i| i.quantity)
+            .sum()
     }
 
     pub fn add(&mut self, item: Item) {
+        if let Some(stack) = self.items.iter_mut().find(|i| i.stacks_with(&item)) {
+            stack.quantity += item.quantity;
+            return;
+        }
+
         self.items.push(item);
         self.items.sort_by(|a, b| a.name().cmp(b.name()));
     }
 
+    /// Lists the items for the client, with counts for more than one (eg: "3 rocks")
+    pub fn describe(&self) -> Vec<String> {
+        let mut output: Vec<(String, usize)> = vec![];
+
+        // sorted by name, so matching items are next to each other
+        for item in &self.items {
+            match output.last_mut() {
+                Some((name, count)) if name == item.name() => *count += item.quantity,
+                _ => output.push((item.name().to_owned(), item.quantity)),
+            }
+        }
+
+        output
+            .into_iter()
+            .map(|(name, count)| match count {
+                1 => name,
+                _ => format!("{} {}s", count, name),
+            })
+            .collect()
+    }
+
+    /// Finds the first item matching the predicate, for changing it in place
+    pub fn find_mut<P: Fn(&Item) -> bool>(&mut self, predicate: P) -> Option<&mut Item> {
+        self.items.iter_mut().find(|i| predicate(i))
+    }
+
+    /// Removes every item, returning them
+    pub fn drain(&mut self) -> Vec<Item> {
+        self.items.drain(..).collect()
+    }
+
+    /// Removes the items that have decayed by the given clock tick, returning them
+    pub fn remove_decayed(&mut self, tick: u64) -> Vec<Item> {
+        let (decayed, kept) = self.items.drain(..).partition(|i| i.is_decayed(tick));
+        self.items = kept;
+        decayed
+    }
+
+    /// Removes a single item by name, splitting it off a stack if need be
     pub fn remove(&mut self, name: &str) -> Option<Item> {
-        match self.items.binary_search_by(|probe| probe.name().cmp(name)) {
-            Ok(idx) => Some(self.items.remove(idx)),
-            Err(_) => None,
+        self.take(name, Quantity::One)
+            .and_then(|mut items| items.pop())
+    }
+
+    /// Removes some of the items matching a name (or its plural), splitting
+    /// stacks as needed. Returns `None` (and leaves everything in place) if
+    /// there aren't enough of them.
+    pub fn take(&mut self, name: &str, quantity: Quantity) -> Option<Vec<Item>> {
+        self.take_matching(|i| i.matches(name), quantity)
+    }
+
+    /// Removes a number of items made from a prototype, splitting stacks as needed
+    pub fn take_prototype(&mut self, prototype: &str, count: usize) -> Option<Vec<Item>> {
+        self.take_matching(|i| i.prototype == prototype, Quantity::Count(count))
+    }
+
+    fn take_matching<P: Fn(&Item) -> bool>(
+        &mut self,
+        predicate: P,
+        quantity: Quantity,
+    ) -> Option<Vec<Item>> {
+        let available: usize = self
+            .items
+            .iter()
+            .filter(|i| predicate(i))
+            .map(|i| i.quantity)
+            .sum();
+
+        let wanted = match quantity {
+            Quantity::One => 1,
+            Quantity::Count(count) => count,
+            Quantity::All => available,
+        };
+
+        if wanted == 0 || wanted > available {
+            return None;
+        }
+
+        let mut output = vec![];
+        let mut remaining = wanted;
+        let mut idx = 0;
+
+        while remaining > 0 && idx < self.items.len() {
+            let item = &mut self.items[idx];
+            if !predicate(item) {
+                idx += 1;
+                continue;
+            }
+
+            if item.quantity <= remaining {
+                remaining -= item.quantity;
+                output.push(self.items.remove(idx));
+            } else {
+                output.push(item.split(remaining));
+                remaining = 0;
+            }
+        }
+
+        Some(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rock() -> Item {
+        let mut rock = Item::new();
+        rock.prototype = "ROCK".to_owned();
+        rock.name = "rock".to_owned();
+        rock.stackable = true;
+        rock
+    }
+
+    #[test]
+    fn test_stacks() {
+        let mut inventory = Inventory::new();
+        for _ in 0..5 {
+            inventory.add(rock());
         }
+
+        let mut stick = Item::new();
+        stick.name = "stick".to_owned();
+        inventory.add(stick.clone());
+        inventory.add(stick);
+
+        assert_eq!(inventory.len(), 3);
+        assert_eq!(inventory.count("ROCK"), 5);
+        assert_eq!(
+            inventory.describe(),
+            vec!["5 rocks".to_owned(), "2 sticks".to_owned()]
+        );
+
+        let taken = inventory.take("rocks", Quantity::Count(3)).unwrap();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].quantity, 3);
+        assert_eq!(inventory.count("ROCK"), 2);
+
+        assert!(inventory.take("rocks", Quantity::Count(3)).is_none());
+        assert_eq!(inventory.take("sticks", Quantity::All).unwrap().len(), 2);
+        assert!(inventory.remove("rock").is_some());
+        assert_eq!(inventory.describe(), vec!["rock".to_owned()]);
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        let args: Vec<String> = vec!["3".into(), "rocks".into()];
+        assert_eq!(Quantity::parse(&args), (Quantity::Count(3), &args[1..]));
+
+        let args: Vec<String> = vec!["all".into(), "rocks".into()];
+        assert_eq!(Quantity::parse(&args), (Quantity::All, &args[1..]));
+
+        let args: Vec<String> = vec!["rock".into()];
+        assert_eq!(Quantity::parse(&args), (Quantity::One, &args[..]));
     }
 }
@@ -5,12 +5,120 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+/// The prototype name given to corpses, which aren't built from templates
+pub const CORPSE: &str = "CORPSE";
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Item {
     pub identifier: Identifier,
     pub prototype: String,
     pub name: String,
     pub description: Description,
+
+    /// Kinds of damage this Item protects against (or leaves its carrier open to)
+    #[serde(default)]
+    pub resistances: Vec<(DamageKind, Resistance)>,
+
+    /// Where this Item is worn or wielded, if it can be equipped at all
+    #[serde(default)]
+    pub slot: Option<Slot>,
+
+    /// How a weapon attacks, replacing its wielder's own attacks
+    #[serde(default)]
+    pub attacks: Vec<Attack>,
+
+    /// Damage soaked up from each hit while this Item is worn
+    #[serde(default)]
+    pub armour: usize,
+
+    /// Changes to its wearer's attributes while this Item is equipped
+    #[serde(default)]
+    pub bonuses: Vec<(Attribute, isize)>,
+
+    /// How heavy a single Item is
+    #[serde(default)]
+    pub weight: usize,
+
+    /// How much room a single Item takes up in its carrier's hands
+    #[serde(default)]
+    pub bulk: usize,
+
+    /// What happens when this Item is eaten, drunk or read
+    #[serde(default)]
+    pub consumable: Option<Consumable>,
+
+    /// Whether Items of this prototype pile up into a single stack
+    #[serde(default)]
+    pub stackable: bool,
+
+    /// How many Items are in this stack
+    #[serde(default = "one")]
+    pub quantity: usize,
+
+    /// Items held inside this one (eg: the belongings left in a corpse)
+    #[serde(default)]
+    pub contents: Inventory,
+
+    /// Money held inside this one (eg: dropped by a Mob when it died)
+    #[serde(default)]
+    pub coins: Wallet,
+
+    /// How many Items fit inside, if this Item is a container
+    #[serde(default)]
+    pub capacity: usize,
+
+    /// Closed containers have to be opened to get at what's inside
+    #[serde(default)]
+    pub closed: bool,
+
+    /// Locked containers can't be opened without the key
+    #[serde(default)]
+    pub locked: bool,
+
+    /// The prototype of the Item that locks and unlocks this container
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// The clock tick when this Item crumbles away, if it doesn't last forever
+    #[serde(default)]
+    pub decays_at: Option<u64>,
+
+    /// How much more wear this Item can take before it breaks
+    #[serde(default)]
+    pub durability: usize,
+
+    /// How much wear this Item could take when it was new. Zero never wears out.
+    #[serde(default)]
+    pub max_durability: usize,
+
+    /// Magic worked into this Item: weapons hit more often and harder, and
+    /// anything else soaks up more damage while worn
+    #[serde(default)]
+    pub enchantment: usize,
+
+    /// A name given to this particular Item by a hero (see `ENGRAVE`)
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Whether this Item binds to the first hero to equip it
+    #[serde(default)]
+    pub binds: bool,
+
+    /// The hero this Item is bound to. Only they can equip it, and it can't be given, sold
+    /// or traded away (though it can still be dropped or put in a container).
+    #[serde(default)]
+    pub bound_to: Option<Identifier>,
+
+    /// Random qualities rolled when this Item spawned
+    #[serde(default)]
+    pub affixes: Vec<Affix>,
+}
+
+/// A random quality an Item can spawn with (eg: a "keen" spear, with a bonus to dexterity)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Affix {
+    pub name: String,
+    pub bonuses: Vec<(Attribute, isize)>,
 }
 
 impl Item {
@@ -20,10 +128,285 @@ impl Item {
             prototype: String::new(),
             name: String::new(),
             description: Description::default(),
+            resistances: vec![],
+            slot: None,
+            attacks: vec![],
+            armour: 0,
+            bonuses: vec![],
+            weight: 0,
+            bulk: 0,
+            consumable: None,
+            stackable: false,
+            quantity: 1,
+            contents: Inventory::new(),
+            coins: Wallet::new(),
+            capacity: 0,
+            closed: false,
+            locked: false,
+            key: None,
+            decays_at: None,
+            durability: 0,
+            max_durability: 0,
+            enchantment: 0,
+            title: None,
+            binds: false,
+            bound_to: None,
+            affixes: vec![],
+        }
+    }
+
+    /// The remains of a Mob, holding everything it carried and wore
+    pub fn corpse(victim: &Mob, decays_at: u64) -> Self {
+        let mut contents = victim.inventory.clone();
+        for item in victim.equipment.items() {
+            contents.add(item.clone());
+        }
+
+        Self {
+            prototype: CORPSE.to_owned(),
+            name: "corpse".to_owned(),
+            description: Description {
+                text: format!("The corpse of {}.", victim.name()),
+                clicks: HashMap::new(),
+            },
+            contents,
+            coins: victim.wallet.clone(),
+            decays_at: Some(decays_at),
+            ..Self::new()
+        }
+    }
+
+    /// Does this Item go by the name (or the plural of it)?
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.plural() == name
+    }
+
+    pub fn plural(&self) -> String {
+        format!("{}s", self.name)
+    }
+
+    /// Describes the stack (eg: "the rock", or "3 rocks")
+    pub fn label(&self) -> String {
+        match self.quantity {
+            1 => format!("the {}", self.name),
+            n => format!("{} {}", n, self.plural()),
+        }
+    }
+
+    /// The weight of the whole stack, including anything inside it
+    pub fn total_weight(&self) -> usize {
+        self.weight * self.quantity + self.contents.weight()
+    }
+
+    /// The room the whole stack takes up, including anything inside it
+    pub fn total_bulk(&self) -> usize {
+        self.bulk * self.quantity + self.contents.bulk()
+    }
+
+    /// Can this Item join the other's stack?
+    pub fn stacks_with(&self, other: &Item) -> bool {
+        self.stackable
+            && other.stackable
+            && self.prototype == other.prototype
+            && self.durability == other.durability
+            && self.enchantment == other.enchantment
+            && self.title == other.title
+            && self.bound_to == other.bound_to
+            && self.affixes == other.affixes
+    }
+
+    /// Worn out Items still exist, but are no use until they're replaced
+    pub fn is_broken(&self) -> bool {
+        self.max_durability > 0 && self.durability == 0
+    }
+
+    /// Wears the Item down a little, returning true if that broke it
+    pub fn wear(&mut self) -> bool {
+        if self.max_durability == 0 || self.durability == 0 {
+            return false;
+        }
+
+        self.durability -= 1;
+        self.durability == 0
+    }
+
+    /// Every attribute bonus this Item gives, including from its affixes
+    pub fn all_bonuses(&self) -> impl Iterator<Item = &(Attribute, isize)> {
+        self.bonuses
+            .iter()
+            .chain(self.affixes.iter().flat_map(|a| a.bonuses.iter()))
+    }
+
+    /// Describes how worn the Item is, if it wears at all
+    pub fn condition(&self) -> Option<&'static str> {
+        if self.max_durability == 0 {
+            return None;
+        }
+
+        let condition = match self.durability * 100 / self.max_durability {
+            0 => "broken",
+            1..=25 => "badly worn",
+            26..=75 => "worn",
+            _ => "in good condition",
+        };
+
+        Some(condition)
+    }
+
+    /// Splits some of this stack off into a new one
+    pub fn split(&mut self, quantity: usize) -> Item {
+        let quantity = std::cmp::min(quantity, self.quantity);
+        self.quantity -= quantity;
+
+        Item {
+            identifier: Identifier::random(),
+            quantity,
+            ..self.clone()
+        }
+    }
+
+    pub fn is_corpse(&self) -> bool {
+        self.prototype == CORPSE
+    }
+
+    pub fn is_container(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Checks that the contents can be reached: it has to be a container (or a
+    /// corpse), and it can't be closed.
+    pub fn check_open(&self) -> Result<(), TCError> {
+        if !(self.is_container() || self.is_corpse()) {
+            return Err(TCError::User(format!(
+                "The {} can't hold anything.",
+                self.name
+            )));
+        }
+
+        if self.closed {
+            return Err(TCError::User(format!("The {} is closed.", self.name)));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that there's room inside for `quantity` more Items. Capacity counts
+    /// items, not stacks, so a pouch for one holds a single rock.
+    pub fn can_hold(&self, quantity: usize) -> Result<(), TCError> {
+        if !self.is_container() {
+            return Err(TCError::User(format!(
+                "You can't put things in the {}.",
+                self.name
+            )));
+        }
+
+        self.check_open()?;
+
+        if self.contents.quantity() + quantity > self.capacity {
+            return Err(TCError::User(format!("The {} is full.", self.name)));
+        }
+
+        Ok(())
+    }
+
+    /// Does the keyring hold the key for this container?
+    pub fn has_key(&self, keyring: &Inventory) -> bool {
+        match &self.key {
+            Some(key) => keyring.count(key) > 0,
+            None => false,
+        }
+    }
+
+    /// Opens a container, unlocking it first if the keyring holds the key.
+    /// Returns true if it had to be unlocked.
+    pub fn open(&mut self, keyring: &Inventory) -> Result<bool, TCError> {
+        if !self.is_container() {
+            return Err(TCError::User(format!("You can't open the {}.", self.name)));
+        }
+
+        if !self.closed {
+            return Err(TCError::User(format!("The {} is already open.", self.name)));
+        }
+
+        let unlocked = self.locked;
+        if self.locked {
+            self.unlock(keyring)?;
+        }
+
+        self.closed = false;
+        Ok(unlocked)
+    }
+
+    pub fn close(&mut self) -> Result<(), TCError> {
+        if !self.is_container() {
+            return Err(TCError::User(format!("You can't close the {}.", self.name)));
+        }
+
+        if self.closed {
+            return Err(TCError::User(format!(
+                "The {} is already closed.",
+                self.name
+            )));
+        }
+
+        self.closed = true;
+        Ok(())
+    }
+
+    /// Locks a closed container, as long as the keyring holds the key
+    pub fn lock(&mut self, keyring: &Inventory) -> Result<(), TCError> {
+        if self.key.is_none() {
+            return Err(TCError::User(format!("The {} has no lock.", self.name)));
+        }
+
+        if self.locked {
+            return Err(TCError::User(format!(
+                "The {} is already locked.",
+                self.name
+            )));
+        }
+
+        if !self.closed {
+            return Err(TCError::User(format!("Close the {} first.", self.name)));
+        }
+
+        if !self.has_key(keyring) {
+            return Err(TCError::user("You don't have the key."));
+        }
+
+        self.locked = true;
+        Ok(())
+    }
+
+    pub fn unlock(&mut self, keyring: &Inventory) -> Result<(), TCError> {
+        if !self.locked {
+            return Err(TCError::User(format!("The {} isn't locked.", self.name)));
+        }
+
+        if !self.has_key(keyring) {
+            return Err(TCError::User(format!(
+                "The {} is locked, and you don't have the key.",
+                self.name
+            )));
+        }
+
+        self.locked = false;
+        Ok(())
+    }
+
+    pub fn is_decayed(&self, tick: u64) -> bool {
+        match self.decays_at {
+            Some(t) => t <= tick,
+            None => false,
         }
     }
 }
 
+/// Items saved before stacks existed are each a stack of one
+fn one() -> usize {
+    1
+}
+
 impl Named for Item {
     fn name(&self) -> &str {
         &self.name
@@ -31,11 +414,60 @@ impl Named for Item {
 }
 
 impl Describe for Item {
-    fn describe(&self, _world: &World) -> Markup {
-        Markup {
-            text: self.description.text.clone(),
-            clicks: self.description.clicks.clone(),
+    fn describe(&self, world: &World) -> Markup {
+        let mut text = self.description.text.clone();
+        let mut clicks = self.description.clicks.clone();
+
+        if let Some(title) = &self.title {
+            // titles are written by players, so they mustn't be read as HTML
+            text += &format!(" It's been named \"{}\".", markup::escape(title));
+        }
+
+        for affix in &self.affixes {
+            let bonuses: Vec<String> = affix
+                .bonuses
+                .iter()
+                .map(|(attribute, bonus)| format!("{:+} {}", bonus, attribute.name()))
+                .collect();
+
+            text += &match bonuses.len() {
+                0 => format!(" It's {}.", affix.name),
+                _ => format!(" It's {} ({}).", affix.name, bonuses.join(", ")),
+            };
+        }
+
+        if self.enchantment > 0 {
+            text += &format!(" It glows with a faint light (+{}).", self.enchantment);
+        }
+
+        if let Some(condition) = self.condition() {
+            text += &format!(
+                " It's {} ({}/{}).",
+                condition, self.durability, self.max_durability
+            );
+        }
+
+        if let Some(owner) = &self.bound_to {
+            match world.mobs.get(owner) {
+                Ok(mob) => text += &format!(" It's bound to {}.", mob.name()),
+                Err(_) => text += " It's bound to someone.",
+            }
         }
+
+        if self.is_container() {
+            let (state, action) = match (self.closed, self.locked) {
+                (true, true) => ("closed and [[locked]]", "unlock"),
+                (true, false) => ("[[closed]]", "open"),
+                (false, _) => ("[[open]]", "look in"),
+            };
+
+            text += &format!(" It's {}.", state);
+            for click in &["locked", "closed", "open"] {
+                clicks.insert(click.to_string(), format!("{} {}", action, self.name));
+            }
+        }
+
+        Markup { text, clicks }
     }
 }
 
@@ -44,3 +476,63 @@ pub struct Description {
     pub text: String,
     pub clicks: HashMap<String, String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_containers() {
+        let mut chest = Item::new();
+        chest.name = "chest".to_owned();
+        chest.capacity = 1;
+        chest.closed = true;
+        chest.locked = true;
+        chest.key = Some("KEY".to_owned());
+
+        let mut keyring = Inventory::new();
+        assert!(chest.open(&keyring).is_err());
+        assert!(chest.can_hold(1).is_err());
+
+        let mut key = Item::new();
+        key.prototype = "KEY".to_owned();
+        keyring.add(key);
+
+        assert_eq!(chest.open(&keyring).ok(), Some(true));
+        assert!(chest.can_hold(1).is_ok());
+
+        chest.contents.add(Item::new());
+        assert!(chest.can_hold(1).is_err());
+
+        // can't lock it while it's open
+        assert!(chest.lock(&keyring).is_err());
+        chest.close().unwrap();
+        chest.lock(&keyring).unwrap();
+        assert!(chest.check_open().is_err());
+    }
+
+    #[test]
+    fn test_wear() {
+        let mut spear = Item::new();
+        spear.stackable = true;
+        spear.max_durability = 2;
+        spear.durability = 2;
+        assert_eq!(spear.condition(), Some("in good condition"));
+
+        // a worn spear no longer stacks with a fresh one
+        let fresh = spear.clone();
+        assert!(!spear.wear());
+        assert!(!spear.stacks_with(&fresh));
+        assert_eq!(spear.condition(), Some("worn"));
+
+        assert!(spear.wear());
+        assert!(spear.is_broken());
+        assert!(!spear.wear());
+
+        // things that don't wear never break
+        let mut rock = Item::new();
+        assert!(!rock.wear());
+        assert!(!rock.is_broken());
+        assert_eq!(rock.condition(), None);
+    }
+}
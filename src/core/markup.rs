@@ -6,3 +6,34 @@ pub struct Markup {
     pub text: String,
     pub clicks: HashMap<String, String>,
 }
+
+/// Escapes text a player wrote, so it shows up as-is rather than as HTML
+pub fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("Old Faithful"), "Old Faithful");
+        assert_eq!(
+            escape("<img src=x onerror='x'>"),
+            "&lt;img src=x onerror=&#39;x&#39;&gt;"
+        );
+    }
+}
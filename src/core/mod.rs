@@ -1,12 +1,30 @@
 /// Provides the authentication framework
 pub mod authentication;
 
+/// Global chat channels
+pub mod channels;
+
 /// In game time calculations
 pub mod clock;
 
+/// Attack and damage rolls
+pub mod combat;
+
+/// Recipes heroes can craft
+pub mod crafting;
+
+/// Food, drink, potions and scrolls
+pub mod consumable;
+
+/// Conversation trees for NPCs
+pub mod dialogue;
+
 /// Random number generation using the dice model
 pub mod dice;
 
+/// Items worn and wielded by Mobs
+pub mod equipment;
+
 /// Entities are tickable, describable, and stateful
 pub mod entities;
 pub mod errors;
@@ -16,32 +34,65 @@ pub mod inventory;
 pub mod item;
 pub mod markup;
 
+/// Groups of mobs that adventure together
+pub mod party;
+
+/// Routes between spaces
+pub mod pathfinding;
+
 /// Parser for input text from players
 pub mod phrase;
 
 /// Represents the Mobs in a given Space
 pub mod population;
 pub mod prototypes;
+
+/// Builder-written NPC behaviour, embedded in templates
+pub mod scripting;
+
+/// NPCs that buy and sell
+pub mod shop;
 pub mod spawn;
 
+/// Timed conditions like poison and stun
+pub mod status;
+
+/// Swapping items and money between heroes
+pub mod trade;
+
 /// Update messages that are sent to the client
 pub mod update;
 
+/// Money carried by Mobs
+pub mod wallet;
+
 /// Universal, shared game state
 pub mod world;
 
 pub use authentication::Authentication;
+pub use channels::Channels;
 pub use clock::{Clock, DateTime, Transition};
+pub use combat::{Attack, Resistance};
+pub use consumable::{Consumable, ConsumableKind, ItemEffect};
+pub use crafting::Recipe;
+pub use dialogue::Dialogue;
 pub use dice::Dice;
+pub use equipment::{Attribute, Equipment, Slot};
 pub use errors::TCError;
 pub use inventory::Inventory;
 pub use item::Item;
 pub use markup::Markup;
+pub use party::Parties;
 pub use phrase::Phrase;
 pub use population::Population;
-pub use prototypes::{ItemPrototype, MobPrototype, Prototyped, Prototypes};
+pub use prototypes::{AffixRoll, ItemPrototype, MobPrototype, Prototyped, Prototypes};
+pub use scripting::Scripts;
+pub use shop::Shop;
 pub use spawn::Spawn;
+pub use status::{Condition, Status, Statuses};
+pub use trade::Trades;
 pub use update::Update;
+pub use wallet::Wallet;
 pub use world::{Command, World};
 
 pub use entities::*;
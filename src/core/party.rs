@@ -0,0 +1,152 @@
+use crate::core::*;
+
+use std::collections::HashMap;
+
+/// Groups of mobs that adventure together: they share a chat channel, fight
+/// together, and split the rewards of a kill.
+///
+/// Each group is keyed by its leader, and its member list always starts with
+/// the leader. A mob that isn't in a group is treated as a group of one.
+#[derive(Debug, Default)]
+pub struct Parties {
+    groups: HashMap<Identifier, Vec<Identifier>>,
+    invites: HashMap<Identifier, Identifier>, // invitee -> leader
+}
+
+impl Parties {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The leader of the identifier's group, if they're in one
+    pub fn leader_of(&self, identifier: &Identifier) -> Option<Identifier> {
+        self.groups
+            .iter()
+            .find(|(_, members)| members.contains(identifier))
+            .map(|(leader, _)| leader.clone())
+    }
+
+    /// Everyone in the identifier's group, including themselves (leader first)
+    pub fn members(&self, identifier: &Identifier) -> Vec<Identifier> {
+        match self.leader_of(identifier) {
+            Some(leader) => self.groups[&leader].clone(),
+            None => vec![identifier.to_owned()],
+        }
+    }
+
+    /// Returns true if both identifiers are in the same group
+    pub fn are_allies(&self, a: &Identifier, b: &Identifier) -> bool {
+        a == b || self.members(a).contains(b)
+    }
+
+    pub fn invite(&mut self, from: &Identifier, to: &Identifier) -> Result<(), TCError> {
+        if let Some(leader) = self.leader_of(from) {
+            if &leader != from {
+                return Err(TCError::user("Only the leader of your group can invite."));
+            }
+        }
+
+        if self.leader_of(to).is_some() {
+            return Err(TCError::user("They're already in a group."));
+        }
+
+        self.invites.insert(to.to_owned(), from.to_owned());
+        Ok(())
+    }
+
+    /// Joins the group the identifier was invited to, returning the leader
+    pub fn accept(&mut self, identifier: &Identifier) -> Result<Identifier, TCError> {
+        let leader = self
+            .invites
+            .remove(identifier)
+            .ok_or_else(|| TCError::user("Nobody has invited you to a group."))?;
+
+        if self.leader_of(identifier).is_some() {
+            return Err(TCError::user("You're already in a group."));
+        }
+
+        // the inviter may have joined someone else's group in the meantime
+        if let Some(current) = self.leader_of(&leader) {
+            if current != leader {
+                return Err(TCError::user("That group isn't recruiting anymore."));
+            }
+        }
+
+        self.groups
+            .entry(leader.clone())
+            .or_insert_with(|| vec![leader.clone()])
+            .push(identifier.to_owned());
+
+        Ok(leader)
+    }
+
+    /// Turns down a pending invitation, returning who sent it
+    pub fn decline(&mut self, identifier: &Identifier) -> Result<Identifier, TCError> {
+        self.invites
+            .remove(identifier)
+            .ok_or_else(|| TCError::user("Nobody has invited you to a group."))
+    }
+
+    /// Removes the identifier from their group (and any invitations), returning the
+    /// members left behind. If the leader leaves, the next member takes over, and a
+    /// group of one is disbanded.
+    pub fn leave(&mut self, identifier: &Identifier) -> Vec<Identifier> {
+        self.invites
+            .retain(|to, from| to != identifier && from != identifier);
+
+        let leader = match self.leader_of(identifier) {
+            Some(l) => l,
+            None => return vec![],
+        };
+
+        let mut members = self.groups.remove(&leader).unwrap_or_default();
+        members.retain(|m| m != identifier);
+
+        if members.len() > 1 {
+            self.groups.insert(members[0].clone(), members.clone());
+        }
+
+        members
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invite_accept_leave() {
+        let mut parties = Parties::new();
+        let leader = Identifier::from("leader");
+        let second = Identifier::from("second");
+        let third = Identifier::from("third");
+
+        assert_eq!(parties.members(&leader), vec![leader.clone()]);
+        assert!(parties.accept(&second).is_err());
+
+        parties.invite(&leader, &second).unwrap();
+        assert_eq!(parties.accept(&second).unwrap(), leader);
+        parties.invite(&leader, &third).unwrap();
+        parties.accept(&third).unwrap();
+
+        assert_eq!(
+            parties.members(&third),
+            vec![leader.clone(), second.clone(), third.clone()]
+        );
+        assert!(parties.are_allies(&second, &third));
+
+        // only the leader can invite
+        assert!(parties
+            .invite(&second, &Identifier::from("fourth"))
+            .is_err());
+
+        // the next member in line takes over when the leader leaves
+        assert_eq!(parties.leave(&leader), vec![second.clone(), third.clone()]);
+        assert_eq!(parties.leader_of(&third), Some(second.clone()));
+
+        // and a group of one is no group at all
+        parties.leave(&third);
+        assert_eq!(parties.leader_of(&second), None);
+        assert!(!parties.are_allies(&second, &third));
+    }
+}
@@ -0,0 +1,83 @@
+use crate::core::Identifier;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Finds the shortest route between two spaces, as a list of exit directions to take.
+/// `exits` lists the (direction, destination) pairs leading out of a space. Returns
+/// `None` if there's no way through, and an empty route if we're already there.
+pub fn find_path<F>(from: &Identifier, to: &Identifier, exits: F) -> Option<Vec<String>>
+where
+    F: Fn(&Identifier) -> Vec<(String, Identifier)>,
+{
+    let mut visited: HashSet<Identifier> = HashSet::new();
+    let mut came_from: HashMap<Identifier, (Identifier, String)> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    visited.insert(from.to_owned());
+    queue.push_back(from.to_owned());
+
+    while let Some(current) = queue.pop_front() {
+        if &current == to {
+            // walk back to the start, then flip it around
+            let mut route = vec![];
+            let mut step = current;
+            while let Some((previous, direction)) = came_from.get(&step) {
+                route.push(direction.to_owned());
+                step = previous.to_owned();
+            }
+            route.reverse();
+            return Some(route);
+        }
+
+        // sorted, so the same route is picked every time
+        let mut next = exits(&current);
+        next.sort();
+
+        for (direction, destination) in next {
+            if visited.insert(destination.clone()) {
+                came_from.insert(destination.clone(), (current.clone(), direction));
+                queue.push_back(destination);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_path() {
+        // field <-> shed <-> cellar, and a one way hole from the field to the cellar
+        let mut graph: HashMap<Identifier, Vec<(String, Identifier)>> = HashMap::new();
+        let field = Identifier::from("FIELD");
+        let shed = Identifier::from("SHED");
+        let cellar = Identifier::from("CELLAR");
+        let island = Identifier::from("ISLAND");
+
+        graph.insert(
+            field.clone(),
+            vec![("in".into(), shed.clone()), ("hole".into(), cellar.clone())],
+        );
+        graph.insert(
+            shed.clone(),
+            vec![
+                ("out".into(), field.clone()),
+                ("down".into(), cellar.clone()),
+            ],
+        );
+        graph.insert(cellar.clone(), vec![("up".into(), shed.clone())]);
+
+        let exits = |id: &Identifier| graph.get(id).cloned().unwrap_or_default();
+
+        assert_eq!(find_path(&field, &field, exits), Some(vec![]));
+        assert_eq!(find_path(&field, &cellar, exits), Some(vec!["hole".into()]));
+        assert_eq!(
+            find_path(&cellar, &field, exits),
+            Some(vec!["up".into(), "out".into()])
+        );
+        assert_eq!(find_path(&field, &island, exits), None);
+    }
+}
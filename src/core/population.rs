@@ -30,7 +30,13 @@ impl Population {
         self.mobs
             .iter()
             .flat_map(|i| world.mobs.get(i))
-            .map(|m| m.name)
+            .map(|m| {
+                if m.ghost {
+                    format!("the ghost of {}", m.name)
+                } else {
+                    m.name
+                }
+            })
             .collect()
     }
 
@@ -1,21 +1,85 @@
 use super::Prototyped;
-use crate::core::item::{Description, Item};
+use crate::core::item::{Affix, Description, Item};
+use crate::core::{Attack, Attribute, Consumable, DamageKind, Dice, Resistance, Slot};
+
+/// A quality Items might spawn with: a 1 in `chance` chance, with each bonus
+/// rolled from a dice string (eg: "1d2")
+#[derive(Debug, Default, Clone)]
+pub struct AffixRoll {
+    pub name: String,
+    pub chance: usize,
+    pub bonuses: Vec<(Attribute, String)>,
+}
 
 #[derive(Debug, Default)]
 pub struct ItemPrototype {
     pub prototype_name: String,
     pub name: String,
     pub description: Description,
+    pub resistances: Vec<(DamageKind, Resistance)>,
+    pub slot: Option<Slot>,
+    pub attacks: Vec<Attack>,
+    pub armour: usize,
+    pub bonuses: Vec<(Attribute, isize)>,
+    pub weight: usize,
+    pub bulk: usize,
+    pub consumable: Option<Consumable>,
+    pub stackable: bool,
+    pub capacity: usize,
+    pub closed: bool,
+    pub locked: bool,
+    pub key: Option<String>,
+    pub durability: Option<String>,
+    pub enchantment: Option<String>,
+    pub binds: bool,
+    pub affixes: Vec<AffixRoll>,
 }
 
 impl Prototyped for ItemPrototype {
     type Item = Item; // gross, I know
 
-    fn create(&self) -> Self::Item {
+    fn create(&self, dice: &mut Dice) -> Self::Item {
         let mut output = Item::new();
         output.prototype = self.prototype_name.clone();
         output.name = self.name.clone();
         output.description = self.description.clone();
+        output.resistances = self.resistances.clone();
+        output.slot = self.slot;
+        output.attacks = self.attacks.clone();
+        output.armour = self.armour;
+        output.bonuses = self.bonuses.clone();
+        output.weight = self.weight;
+        output.bulk = self.bulk;
+        output.consumable = self.consumable.clone();
+        output.stackable = self.stackable;
+        output.capacity = self.capacity;
+        output.closed = self.closed;
+        output.locked = self.locked;
+        output.key = self.key.clone();
+        output.binds = self.binds;
+
+        // every Item rolls its own durability, enchantment and affixes
+        if let Some(durability) = &self.durability {
+            output.max_durability = dice.roll(durability).unwrap_or(0);
+            output.durability = output.max_durability;
+        }
+
+        if let Some(enchantment) = &self.enchantment {
+            output.enchantment = dice.roll(enchantment).unwrap_or(0);
+        }
+
+        for affix in &self.affixes {
+            if dice.range(0, std::cmp::max(affix.chance, 1)) == 0 {
+                output.affixes.push(Affix {
+                    name: affix.name.clone(),
+                    bonuses: affix
+                        .bonuses
+                        .iter()
+                        .map(|(a, roll)| (*a, dice.roll(roll).unwrap_or(0) as isize))
+                        .collect(),
+                });
+            }
+        }
 
         output
     }
@@ -31,6 +95,23 @@ impl ItemPrototype {
             prototype_name: String::new(),
             name: String::new(),
             description: Description::default(),
+            resistances: vec![],
+            slot: None,
+            attacks: vec![],
+            armour: 0,
+            bonuses: vec![],
+            weight: 0,
+            bulk: 0,
+            consumable: None,
+            stackable: false,
+            capacity: 0,
+            closed: false,
+            locked: false,
+            key: None,
+            durability: None,
+            enchantment: None,
+            binds: false,
+            affixes: vec![],
         }
     }
 }
@@ -15,6 +15,14 @@ pub struct MobPrototype {
     pub intelligence: usize,
     pub wisdom: usize,
     pub charisma: usize,
+    pub attacks: Vec<Attack>,
+    pub attacks_per_round: usize,
+    pub resistances: Vec<(DamageKind, Resistance)>,
+    pub behaviour: Behaviour,
+    pub movement: Movement,
+    pub wimpy: usize,
+    pub coins: Option<String>,
+    pub shop: Option<Shop>,
 }
 
 impl MobPrototype {
@@ -26,7 +34,7 @@ impl MobPrototype {
 impl Prototyped for MobPrototype {
     type Item = Mob;
 
-    fn create(&self) -> Self::Item {
+    fn create(&self, dice: &mut Dice) -> Self::Item {
         let mut output = Mob::new();
 
         output.prototype = self.prototype_name();
@@ -40,6 +48,17 @@ impl Prototyped for MobPrototype {
         output.intelligence = self.intelligence;
         output.wisdom = self.wisdom;
         output.charisma = self.charisma;
+        output.attacks = self.attacks.clone();
+        output.attacks_per_round = std::cmp::max(self.attacks_per_round, 1);
+        output.resistances = self.resistances.clone();
+        output.behaviour = self.behaviour.clone();
+        output.movement = self.movement.clone();
+        output.wimpy = self.wimpy;
+        output.shop = self.shop.clone();
+
+        if let Some(coins) = &self.coins {
+            output.wallet = Wallet::with(dice.roll(coins).unwrap_or(0));
+        }
 
         output
     }
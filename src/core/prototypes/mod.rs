@@ -1,9 +1,10 @@
 pub mod item_prototype;
 pub mod mob_prototype;
 
-pub use item_prototype::ItemPrototype;
+pub use item_prototype::{AffixRoll, ItemPrototype};
 pub use mob_prototype::MobPrototype;
 
+use crate::core::Dice;
 use log::{trace, warn};
 
 use std::collections::HashMap;
@@ -18,14 +19,15 @@ impl<T: Prototyped> Prototypes<T> {
         self.things.insert(p.prototype_name(), p);
     }
 
-    pub fn create(&self, key: &str) -> Option<T::Item> {
+    /// Creates a new thing from the named prototype, rolling any of its random properties
+    pub fn create(&self, key: &str, dice: &mut Dice) -> Option<T::Item> {
         match self.things.get(key) {
             None => {
                 warn!("FAILED TO SPAWN - {}", key);
                 None
             }
             Some(prototype) => {
-                let thing = prototype.create();
+                let thing = prototype.create(dice);
                 trace!("SPAWNING - {}", key);
                 Some(thing)
             }
@@ -35,6 +37,6 @@ impl<T: Prototyped> Prototypes<T> {
 
 pub trait Prototyped {
     type Item;
-    fn create(&self) -> Self::Item;
+    fn create(&self, dice: &mut Dice) -> Self::Item;
     fn prototype_name(&self) -> String;
 }
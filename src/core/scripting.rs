@@ -0,0 +1,262 @@
+use crate::core::*;
+
+use log::{trace, warn};
+use rhai::{CallFnOptions, Engine, Scope, AST};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// The most operations a single handler can run before it's cut off
+const MAX_OPERATIONS: u64 = 10_000;
+
+/// The most intents a single handler can queue up
+const MAX_INTENTS: usize = 5;
+
+/// Something that happened to a scripted Mob, and the handler it calls
+#[derive(Debug)]
+pub enum Event {
+    /// Every world tick: `fn on_tick(me)`
+    Tick,
+
+    /// A hero arrived in the Mob's space: `fn on_enter(me, who)`
+    Enter(String),
+
+    /// Someone spoke in the Mob's space: `fn on_say(me, who, message)`
+    Say(String, String),
+
+    /// Someone started a fight with the Mob: `fn on_attacked(me, attacker)`
+    Attacked(String),
+}
+
+impl Event {
+    fn handler(&self) -> &'static str {
+        match self {
+            Self::Tick => "on_tick",
+            Self::Enter(_) => "on_enter",
+            Self::Say(_, _) => "on_say",
+            Self::Attacked(_) => "on_attacked",
+        }
+    }
+}
+
+/// What a script asked its Mob to do. Scripts can't touch the World directly;
+/// the World carries these out once the handler has finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Intent {
+    Say(String),
+    Go(String),
+    SpawnItem(String),
+    Attack(String),
+}
+
+/// The `me` handed to script handlers: a read-only snapshot of the Mob, plus
+/// methods that queue up intents.
+#[derive(Debug, Clone)]
+struct Me {
+    name: String,
+    health: i64,
+    fighting: bool,
+    night: bool,
+    intents: Arc<Mutex<Vec<Intent>>>,
+}
+
+impl Me {
+    fn intend(&mut self, intent: Intent) {
+        let mut intents = self.intents.lock().unwrap();
+        if intents.len() < MAX_INTENTS {
+            intents.push(intent);
+        }
+    }
+}
+
+/// Compiled NPC scripts, keyed by Mob prototype. Builders attach a script to a
+/// mob template, and define handlers for the events they care about:
+///
+/// ```rhai
+/// fn on_say(me, who, message) {
+///     if message.contains("hello") {
+///         me.say("Bzzz, " + who + "!");
+///     }
+/// }
+/// ```
+///
+/// `me` has `name`, `health` (percent), `fighting` and `night`, and can `say`,
+/// `walk` through an exit, `spawn` an item by prototype, or `attack` someone by name.
+pub struct Scripts {
+    engine: Engine,
+    scripts: HashMap<String, AST>,
+}
+
+impl Default for Scripts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Scripts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scripts")
+            .field("prototypes", &self.scripts.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Scripts {
+    pub fn new() -> Self {
+        let mut engine = Engine::new();
+
+        // keep scripts on a short leash
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(16)
+            .set_max_string_size(1_000)
+            .disable_symbol("eval")
+            .on_print(|s| trace!("SCRIPT: {}", s));
+
+        engine
+            .register_type_with_name::<Me>("Me")
+            .register_get("name", |me: &mut Me| me.name.clone())
+            .register_get("health", |me: &mut Me| me.health)
+            .register_get("fighting", |me: &mut Me| me.fighting)
+            .register_get("night", |me: &mut Me| me.night)
+            .register_fn("say", |me: &mut Me, text: &str| {
+                me.intend(Intent::Say(text.to_owned()))
+            })
+            .register_fn("walk", |me: &mut Me, direction: &str| {
+                me.intend(Intent::Go(direction.to_lowercase()))
+            })
+            .register_fn("spawn", |me: &mut Me, prototype: &str| {
+                me.intend(Intent::SpawnItem(prototype.to_owned()))
+            })
+            .register_fn("attack", |me: &mut Me, name: &str| {
+                me.intend(Intent::Attack(name.to_owned()))
+            });
+
+        Self {
+            engine,
+            scripts: HashMap::new(),
+        }
+    }
+
+    /// Compiles a script for a Mob prototype
+    pub fn load(&mut self, prototype: &str, source: &str) -> Result<(), TCError> {
+        let ast = self
+            .engine
+            .compile(source)
+            .map_err(|e| TCError::System(format!("Script for {}: {}", prototype, e)))?;
+
+        self.scripts.insert(prototype.to_owned(), ast);
+        Ok(())
+    }
+
+    pub fn has(&self, prototype: &str) -> bool {
+        self.scripts.contains_key(prototype)
+    }
+
+    /// Runs the Mob's handler for the event (if it has one), returning what it wants to do
+    pub fn run(&self, mob: &Mob, night: bool, event: Event) -> Vec<Intent> {
+        let ast = match self.scripts.get(&mob.prototype) {
+            Some(a) => a,
+            None => return vec![],
+        };
+
+        let handler = event.handler();
+        if !ast.iter_functions().any(|f| f.name == handler) {
+            return vec![];
+        }
+
+        let intents = Arc::new(Mutex::new(vec![]));
+        let me = Me {
+            name: mob.name.clone(),
+            health: mob.health() as i64,
+            fighting: !mob.enemies.is_empty(),
+            night,
+            intents: intents.clone(),
+        };
+
+        let options = CallFnOptions::new().eval_ast(false);
+        let mut scope = Scope::new();
+
+        let result = match event {
+            Event::Tick => self.engine.call_fn_with_options::<rhai::Dynamic>(
+                options,
+                &mut scope,
+                ast,
+                handler,
+                (me,),
+            ),
+            Event::Enter(who) | Event::Attacked(who) => {
+                self.engine.call_fn_with_options::<rhai::Dynamic>(
+                    options,
+                    &mut scope,
+                    ast,
+                    handler,
+                    (me, who),
+                )
+            }
+            Event::Say(who, message) => self.engine.call_fn_with_options::<rhai::Dynamic>(
+                options,
+                &mut scope,
+                ast,
+                handler,
+                (me, who, message),
+            ),
+        };
+
+        if let Err(e) = result {
+            warn!("Script {} for {} failed: {}", handler, mob.prototype, e);
+            return vec![];
+        }
+
+        let output = intents.lock().unwrap().drain(..).collect();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handlers_queue_intents() {
+        let mut scripts = Scripts::new();
+        scripts
+            .load(
+                "GNAT",
+                r#"
+                fn on_say(me, who, message) {
+                    if message.contains("hello") {
+                        me.say("Hello, " + who + "!");
+                        me.walk("North");
+                    }
+                }
+
+                fn on_tick(me) {
+                    loop { }
+                }
+                "#,
+            )
+            .unwrap();
+
+        let mut gnat = Mob::new();
+        gnat.prototype = "GNAT".to_owned();
+        gnat.constitution = 1;
+
+        let said = Event::Say("Bob".to_owned(), "well hello there".to_owned());
+        assert_eq!(
+            scripts.run(&gnat, false, said),
+            vec![
+                Intent::Say("Hello, Bob!".to_owned()),
+                Intent::Go("north".to_owned())
+            ]
+        );
+
+        // runaway scripts are cut off, and missing handlers do nothing
+        assert!(scripts.run(&gnat, false, Event::Tick).is_empty());
+        assert!(scripts
+            .run(&gnat, false, Event::Attacked("Bob".to_owned()))
+            .is_empty());
+        assert!(scripts.load("BROKEN", "fn on_tick(me) {").is_err());
+    }
+}
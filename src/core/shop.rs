@@ -0,0 +1,93 @@
+use crate::core::combat::modifier;
+use crate::core::*;
+
+use serde::{Deserialize, Serialize};
+
+/// How much (in percent) each point of charisma modifier moves a price
+const CHARISMA_DISCOUNT: isize = 5;
+
+/// Something a shopkeeper sells, restocked like a Space spawns items:
+/// a 1 in `chance` chance each tick, up to `max` in stock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ware {
+    pub restock: Spawn,
+
+    /// The list price, in copper
+    pub price: usize,
+}
+
+/// The wares an NPC trades in. They only buy what they sell.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Shop {
+    pub wares: Vec<Ware>,
+}
+
+impl Shop {
+    /// The list price for an Item prototype, if the shop trades in it
+    pub fn price(&self, prototype: &str) -> Option<usize> {
+        self.wares
+            .iter()
+            .find(|w| w.restock.name == prototype)
+            .map(|w| w.price)
+    }
+
+    /// What a hero pays: charming heroes get a discount, and rude ones pay extra
+    pub fn asking_price(&self, prototype: &str, customer: &Mob) -> Option<usize> {
+        let price = self.price(prototype)? as isize;
+        let adjustment = 100 - CHARISMA_DISCOUNT * charm(customer);
+
+        Some(std::cmp::max(price * adjustment / 100, 1) as usize)
+    }
+
+    /// What the shop pays a hero: half the list price, adjusted by charisma the
+    /// other way, but never more than the shop would sell it for
+    pub fn offer(&self, prototype: &str, customer: &Mob) -> Option<usize> {
+        let price = self.price(prototype)? as isize;
+        let adjustment = 100 + CHARISMA_DISCOUNT * charm(customer);
+        let offer = std::cmp::max(price / 2 * adjustment / 100, 0) as usize;
+
+        Some(std::cmp::min(
+            offer,
+            self.asking_price(prototype, customer)?,
+        ))
+    }
+}
+
+/// The customer's charisma modifier, kept within reason
+fn charm(customer: &Mob) -> isize {
+    modifier(customer.attribute(Attribute::Charisma)).clamp(-5, 5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charisma_prices() {
+        let shop = Shop {
+            wares: vec![Ware {
+                restock: Spawn {
+                    name: "BREAD".to_owned(),
+                    chance: 1,
+                    max: 5,
+                },
+                price: 100,
+            }],
+        };
+
+        let mut customer = Mob::new();
+        customer.charisma = 10;
+        assert_eq!(shop.asking_price("BREAD", &customer), Some(100));
+        assert_eq!(shop.offer("BREAD", &customer), Some(50));
+
+        customer.charisma = 14;
+        assert_eq!(shop.asking_price("BREAD", &customer), Some(90));
+        assert_eq!(shop.offer("BREAD", &customer), Some(55));
+
+        customer.charisma = 1;
+        assert_eq!(shop.asking_price("BREAD", &customer), Some(125));
+        assert_eq!(shop.offer("BREAD", &customer), Some(37));
+
+        assert_eq!(shop.price("ROCK"), None);
+    }
+}
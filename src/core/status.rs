@@ -0,0 +1,229 @@
+use crate::core::DamageKind;
+
+use serde::{Deserialize, Serialize};
+
+/// The most poison that can build up in a Mob at once
+pub const MAX_POISON: usize = 5;
+
+/// The longest (in ticks) a Mob can be left burning, however much fire piles on
+pub const MAX_BURNING: usize = 9;
+
+/// Lingering conditions that can afflict (or bless) a Mob
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Condition {
+    /// Loses health every tick and doesn't heal naturally. Stacks up to `MAX_POISON`.
+    Poisoned,
+
+    /// Loses health every tick. Fresh flames only make the fire last longer,
+    /// up to `MAX_BURNING` ticks.
+    Burning,
+
+    /// Can't attack. A Mob can't be stunned again until the first stun wears off.
+    Stunned,
+
+    /// Hits more often. A stronger blessing replaces a weaker one.
+    Blessed,
+}
+
+impl Condition {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Poisoned => "poisoned",
+            Self::Burning => "burning",
+            Self::Stunned => "stunned",
+            Self::Blessed => "blessed",
+        }
+    }
+
+    /// What the afflicted Mob sees when the condition takes hold
+    pub fn onset_message(self) -> &'static str {
+        match self {
+            Self::Poisoned => "You've been poisoned!",
+            Self::Burning => "You catch fire!",
+            Self::Stunned => "You're stunned!",
+            Self::Blessed => "You feel blessed.",
+        }
+    }
+
+    /// What the afflicted Mob sees when the condition wears off (or is cured)
+    pub fn expiry_message(self) -> &'static str {
+        match self {
+            Self::Poisoned => "The poison leaves your body.",
+            Self::Burning => "The flames die out.",
+            Self::Stunned => "You shake off the stun.",
+            Self::Blessed => "Your blessing fades.",
+        }
+    }
+}
+
+/// One condition on a Mob, with how many ticks are left and how strong it is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Status {
+    pub condition: Condition,
+    pub ticks: usize,
+    pub potency: usize,
+}
+
+impl Status {
+    /// The condition (if any) a hit of this kind of damage leaves behind. Poison
+    /// lingers, fire spreads, and a critical blunt blow leaves the target stunned.
+    pub fn from_damage(kind: DamageKind, critical: bool) -> Option<Self> {
+        let (condition, ticks) = match (kind, critical) {
+            (DamageKind::Poison, _) => (Condition::Poisoned, 5),
+            (DamageKind::Fire, _) => (Condition::Burning, 3),
+            (DamageKind::Blunt, true) => (Condition::Stunned, 2),
+            _ => return None,
+        };
+
+        Some(Self {
+            condition,
+            ticks,
+            potency: 1,
+        })
+    }
+}
+
+/// The conditions currently affecting a Mob. Each condition appears at most once,
+/// and adding it again follows the stacking rules documented on `Condition`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Statuses {
+    statuses: Vec<Status>,
+}
+
+impl Statuses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Afflicts the Mob with a condition, returning true if it's new
+    pub fn add(&mut self, condition: Condition, ticks: usize, potency: usize) -> bool {
+        let existing = match self.statuses.iter_mut().find(|s| s.condition == condition) {
+            Some(s) => s,
+            None => {
+                self.statuses.push(Status {
+                    condition,
+                    ticks,
+                    potency,
+                });
+                return true;
+            }
+        };
+
+        match condition {
+            Condition::Poisoned => {
+                existing.potency = std::cmp::min(existing.potency + potency, MAX_POISON);
+                existing.ticks = std::cmp::max(existing.ticks, ticks);
+            }
+            Condition::Burning => {
+                existing.ticks = std::cmp::min(existing.ticks + ticks, MAX_BURNING);
+            }
+            Condition::Stunned => {}
+            Condition::Blessed => {
+                if potency >= existing.potency {
+                    existing.potency = potency;
+                    existing.ticks = ticks;
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn has(&self, condition: Condition) -> bool {
+        self.statuses.iter().any(|s| s.condition == condition)
+    }
+
+    /// How strong a condition is, or zero if the Mob doesn't have it
+    pub fn potency(&self, condition: Condition) -> usize {
+        self.statuses
+            .iter()
+            .find(|s| s.condition == condition)
+            .map(|s| s.potency)
+            .unwrap_or(0)
+    }
+
+    /// Removes a condition, returning true if the Mob had it
+    pub fn cure(&mut self, condition: Condition) -> bool {
+        let before = self.statuses.len();
+        self.statuses.retain(|s| s.condition != condition);
+        before != self.statuses.len()
+    }
+
+    /// Removes every condition, returning the ones that were cured
+    pub fn clear(&mut self) -> Vec<Condition> {
+        self.statuses.drain(..).map(|s| s.condition).collect()
+    }
+
+    /// Counts down every condition by one tick, returning those that wore off
+    pub fn tick(&mut self) -> Vec<Condition> {
+        let mut expired = vec![];
+
+        for status in self.statuses.iter_mut() {
+            status.ticks = status.ticks.saturating_sub(1);
+            if status.ticks == 0 {
+                expired.push(status.condition);
+            }
+        }
+
+        self.statuses.retain(|s| s.ticks > 0);
+        expired
+    }
+
+    /// Describes the current conditions for the client (eg: "poisoned x2")
+    pub fn describe(&self) -> Vec<String> {
+        self.statuses
+            .iter()
+            .map(|s| match s.condition {
+                Condition::Poisoned if s.potency > 1 => {
+                    format!("{} x{}", s.condition.name(), s.potency)
+                }
+                _ => s.condition.name().to_owned(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stacking() {
+        let mut statuses = Statuses::new();
+
+        // poison stacks, up to a limit
+        assert!(statuses.add(Condition::Poisoned, 5, 2));
+        assert!(!statuses.add(Condition::Poisoned, 3, 2));
+        assert_eq!(statuses.potency(Condition::Poisoned), 4);
+        statuses.add(Condition::Poisoned, 3, 2);
+        assert_eq!(statuses.potency(Condition::Poisoned), MAX_POISON);
+
+        // stuns don't refresh
+        statuses.add(Condition::Stunned, 2, 1);
+        statuses.add(Condition::Stunned, 10, 1);
+        assert_eq!(statuses.tick(), vec![]);
+        assert_eq!(statuses.tick(), vec![Condition::Stunned]);
+        assert!(!statuses.has(Condition::Stunned));
+
+        // weaker blessings don't replace stronger ones
+        statuses.add(Condition::Blessed, 5, 3);
+        statuses.add(Condition::Blessed, 50, 1);
+        assert_eq!(statuses.potency(Condition::Blessed), 3);
+
+        assert!(statuses.cure(Condition::Poisoned));
+        assert!(!statuses.cure(Condition::Poisoned));
+        assert_eq!(statuses.describe(), vec!["blessed".to_owned()]);
+
+        // fire burns longer, but not forever
+        for _ in 0..10 {
+            statuses.add(Condition::Burning, 3, 1);
+        }
+        for _ in 1..MAX_BURNING {
+            statuses.tick();
+        }
+        assert!(statuses.has(Condition::Burning));
+        statuses.tick();
+        assert!(!statuses.has(Condition::Burning));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::core::*;
+
+use std::collections::HashMap;
+
+/// What one side puts on the table. The items are copies for show: nothing
+/// changes hands until both sides confirm.
+#[derive(Debug, Clone)]
+pub struct Offer {
+    pub identifier: Identifier,
+    pub items: Vec<Item>,
+    pub copper: usize,
+    pub confirmed: bool,
+}
+
+impl Offer {
+    fn new(identifier: &Identifier) -> Self {
+        Self {
+            identifier: identifier.to_owned(),
+            items: vec![],
+            copper: 0,
+            confirmed: false,
+        }
+    }
+
+    /// Adds items to the offer, merging them with any of the same name
+    pub fn add(&mut self, item: Item) {
+        match self.items.iter_mut().find(|i| i.name() == item.name()) {
+            Some(existing) => existing.quantity += item.quantity,
+            None => self.items.push(item),
+        }
+    }
+
+    /// Takes items off the offer, returning true if there were any
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.items.len();
+        self.items.retain(|i| !i.matches(name));
+        self.items.len() != before
+    }
+
+    /// Describes the offer (eg: "3 rocks and 5 copper")
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = self.items.iter().map(|i| i.label()).collect();
+        if self.copper > 0 {
+            parts.push(wallet::describe(self.copper));
+        }
+
+        match parts.len() {
+            0 => "nothing".to_owned(),
+            1 => parts.remove(0),
+            _ => {
+                let last = parts.pop().unwrap_or_default();
+                format!("{} and {}", parts.join(", "), last)
+            }
+        }
+    }
+}
+
+/// A swap between two heroes: both add items and money, then confirm. Any
+/// change to either offer takes back both confirmations.
+#[derive(Debug, Clone)]
+pub struct Trade {
+    pub offers: [Offer; 2],
+}
+
+impl Trade {
+    fn new(a: &Identifier, b: &Identifier) -> Self {
+        Self {
+            offers: [Offer::new(a), Offer::new(b)],
+        }
+    }
+
+    pub fn involves(&self, identifier: &Identifier) -> bool {
+        self.offers.iter().any(|o| &o.identifier == identifier)
+    }
+
+    /// The identifier's side of the trade
+    pub fn offer(&self, identifier: &Identifier) -> Option<&Offer> {
+        self.offers.iter().find(|o| &o.identifier == identifier)
+    }
+
+    /// The other side of the trade
+    pub fn counter_offer(&self, identifier: &Identifier) -> Option<&Offer> {
+        self.offers.iter().find(|o| &o.identifier != identifier)
+    }
+
+    /// Changes the identifier's offer, taking back both confirmations
+    pub fn amend<F: FnOnce(&mut Offer)>(&mut self, identifier: &Identifier, change: F) {
+        self.unconfirm();
+
+        if let Some(offer) = self.offers.iter_mut().find(|o| &o.identifier == identifier) {
+            change(offer);
+        }
+    }
+
+    /// Confirms the identifier's side, returning true once both sides have
+    pub fn confirm(&mut self, identifier: &Identifier) -> bool {
+        if let Some(offer) = self.offers.iter_mut().find(|o| &o.identifier == identifier) {
+            offer.confirmed = true;
+        }
+
+        self.offers.iter().all(|o| o.confirmed)
+    }
+
+    pub fn unconfirm(&mut self) {
+        for offer in self.offers.iter_mut() {
+            offer.confirmed = false;
+        }
+    }
+}
+
+/// Trades in progress, and the requests to start them
+#[derive(Debug, Default)]
+pub struct Trades {
+    sessions: Vec<Trade>,
+    requests: HashMap<Identifier, Identifier>, // invitee -> proposer
+}
+
+impl Trades {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, identifier: &Identifier) -> Option<&Trade> {
+        self.sessions.iter().find(|t| t.involves(identifier))
+    }
+
+    pub fn get_mut(&mut self, identifier: &Identifier) -> Option<&mut Trade> {
+        self.sessions.iter_mut().find(|t| t.involves(identifier))
+    }
+
+    pub fn propose(&mut self, from: &Identifier, to: &Identifier) -> Result<(), TCError> {
+        if self.get(from).is_some() {
+            return Err(TCError::user("You're already trading."));
+        }
+
+        if self.get(to).is_some() {
+            return Err(TCError::user("They're busy trading with someone else."));
+        }
+
+        self.requests.insert(to.to_owned(), from.to_owned());
+        Ok(())
+    }
+
+    /// Starts trading with whoever asked, returning who that was
+    pub fn accept(&mut self, identifier: &Identifier) -> Result<Identifier, TCError> {
+        let proposer = self
+            .requests
+            .remove(identifier)
+            .ok_or_else(|| TCError::user("Nobody has asked to trade with you."))?;
+
+        if self.get(identifier).is_some() {
+            return Err(TCError::user("You're already trading."));
+        }
+
+        if self.get(&proposer).is_some() {
+            return Err(TCError::user("They've started trading with someone else."));
+        }
+
+        self.sessions.push(Trade::new(&proposer, identifier));
+        Ok(proposer)
+    }
+
+    /// Turns down a request to trade, returning who asked
+    pub fn decline(&mut self, identifier: &Identifier) -> Result<Identifier, TCError> {
+        self.requests
+            .remove(identifier)
+            .ok_or_else(|| TCError::user("Nobody has asked to trade with you."))
+    }
+
+    /// Calls off the identifier's trade (and any request they made), returning it
+    pub fn cancel(&mut self, identifier: &Identifier) -> Option<Trade> {
+        self.requests
+            .retain(|to, from| to != identifier && from != identifier);
+
+        let idx = self.sessions.iter().position(|t| t.involves(identifier))?;
+        Some(self.sessions.remove(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmations() {
+        let (alice, bob) = (Identifier::random(), Identifier::random());
+
+        let mut trades = Trades::new();
+        assert!(trades.accept(&bob).is_err());
+
+        trades.propose(&alice, &bob).unwrap();
+        assert_eq!(trades.accept(&bob).unwrap(), alice);
+        assert!(trades.propose(&alice, &bob).is_err());
+
+        let trade = trades.get_mut(&alice).unwrap();
+        trade.amend(&alice, |o| o.copper = 5);
+        assert!(!trade.confirm(&alice));
+        assert!(trade.offer(&alice).unwrap().confirmed);
+
+        // changing an offer takes back both confirmations
+        let mut rock = Item::new();
+        rock.name = "rock".to_owned();
+        trade.amend(&bob, |o| o.add(rock));
+        assert!(!trade.offer(&alice).unwrap().confirmed);
+
+        assert!(!trade.confirm(&bob));
+        assert!(trade.confirm(&alice));
+        assert_eq!(trade.counter_offer(&bob).unwrap().describe(), "5 copper");
+
+        assert!(trades.cancel(&bob).is_some());
+        assert!(trades.get(&alice).is_none());
+    }
+}
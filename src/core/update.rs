@@ -30,6 +30,16 @@ impl Update {
         Update::new(to, wrapper)
     }
 
+    pub fn chat(to: &Identifier, message: &str) -> Self {
+        let wrapper = Wrapper::Chat(message.to_owned());
+        Update::new(to, wrapper)
+    }
+
+    pub fn tell(to: &Identifier, message: &str) -> Self {
+        let wrapper = Wrapper::Tell(message.to_owned());
+        Update::new(to, wrapper)
+    }
+
     pub fn combat(to: &Identifier, message: String) -> Self {
         let wrapper = Wrapper::Combat(message);
         Update::new(to, wrapper)
@@ -45,6 +55,11 @@ impl Update {
         Update::new(to, wrapper)
     }
 
+    pub fn dialogue(to: &Identifier, content: Markup) -> Self {
+        let wrapper = Wrapper::Dialogue(content);
+        Update::new(to, wrapper)
+    }
+
     pub fn item(to: &Identifier, content: Markup) -> Self {
         let wrapper = Wrapper::Item(content);
         Update::new(to, wrapper)
@@ -56,12 +71,22 @@ impl Update {
     }
 
     pub fn inventory(to: &Identifier, inventory: &Inventory) -> Self {
-        let items = inventory
-            .items()
-            .iter()
-            .map(|i| i.name().to_owned())
-            .collect();
-        let wrapper = Wrapper::Inventory(items);
+        let wrapper = Wrapper::Inventory(inventory.describe());
+        Update::new(to, wrapper)
+    }
+
+    pub fn equipment(to: &Identifier, equipment: &Equipment) -> Self {
+        let wrapper = Wrapper::Equipment(equipment.describe());
+        Update::new(to, wrapper)
+    }
+
+    pub fn wallet(to: &Identifier, wallet: &Wallet) -> Self {
+        let wrapper = Wrapper::Wallet(wallet.describe());
+        Update::new(to, wrapper)
+    }
+
+    pub fn who(to: &Identifier, listing: Vec<String>) -> Self {
+        let wrapper = Wrapper::Who(listing);
         Update::new(to, wrapper)
     }
 
@@ -70,6 +95,11 @@ impl Update {
         Update::new(to, wrapper)
     }
 
+    pub fn status(to: &Identifier, statuses: &Statuses) -> Self {
+        let wrapper = Wrapper::Status(statuses.describe());
+        Update::new(to, wrapper)
+    }
+
     pub fn transition(to: &Identifier, message: &str) -> Self {
         let wrapper = Wrapper::Info(message.to_string());
         Update::new(to, wrapper)
@@ -83,11 +113,18 @@ pub enum Wrapper {
     Error(String),
     Extra(String),
     Combat(String),
+    Chat(String),
+    Tell(String),
     Space(Markup),
     Character(Markup),
     Item(Markup),
+    Dialogue(Markup),
     Population(Vec<String>),
     Time(DateTime),
     Inventory(Vec<String>),
+    Equipment(Vec<String>),
+    Wallet(String),
     Health(usize),
+    Who(Vec<String>),
+    Status(Vec<String>),
 }
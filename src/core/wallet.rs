@@ -0,0 +1,164 @@
+use crate::core::TCError;
+
+use serde::{Deserialize, Serialize};
+
+/// The coins in circulation, and what each is worth in copper
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Denomination {
+    Gold,
+    Silver,
+    Copper,
+}
+
+impl Denomination {
+    pub const ALL: [Denomination; 3] = [Self::Gold, Self::Silver, Self::Copper];
+
+    pub fn value(self) -> usize {
+        match self {
+            Self::Gold => 100,
+            Self::Silver => 10,
+            Self::Copper => 1,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Gold => "gold",
+            Self::Silver => "silver",
+            Self::Copper => "copper",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_ref() {
+            "gold" | "gp" => Some(Self::Gold),
+            "silver" | "sp" => Some(Self::Silver),
+            "copper" | "cp" | "coin" | "coins" => Some(Self::Copper),
+            _ => None,
+        }
+    }
+
+    /// Splits an amount of money off the front of the arguments (eg: "30", or
+    /// "2 gold"), returning it in copper along with the rest of the arguments.
+    /// Amounts too big to count come out as more than anyone could have.
+    pub fn parse(args: &[String]) -> Option<(usize, &[String])> {
+        let (count, rest) = args.split_first()?;
+        let count: usize = count.parse().ok()?;
+
+        match rest.split_first() {
+            Some((name, remainder)) => match Self::from_name(name) {
+                Some(d) => Some((count.saturating_mul(d.value()), remainder)),
+                None => Some((count, rest)),
+            },
+            None => Some((count, rest)),
+        }
+    }
+}
+
+/// Money carried by a Mob, kept in copper and shown in the largest coins that fit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Wallet {
+    copper: usize,
+}
+
+impl Wallet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(copper: usize) -> Self {
+        Self { copper }
+    }
+
+    /// Everything in the wallet, in copper
+    pub fn total(&self) -> usize {
+        self.copper
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.copper == 0
+    }
+
+    pub fn deposit(&mut self, copper: usize) {
+        self.copper = self.copper.saturating_add(copper);
+    }
+
+    pub fn withdraw(&mut self, copper: usize) -> Result<(), TCError> {
+        if copper > self.copper {
+            return Err(TCError::user("You don't have that much money."));
+        }
+
+        self.copper -= copper;
+        Ok(())
+    }
+
+    /// Empties the wallet, returning what was in it
+    pub fn empty(&mut self) -> usize {
+        std::mem::take(&mut self.copper)
+    }
+
+    /// Describes the money in coins (eg: "1 gold, 2 silver and 3 copper")
+    pub fn describe(&self) -> String {
+        describe(self.copper)
+    }
+}
+
+/// Describes an amount of copper in coins (eg: "1 gold and 5 copper")
+pub fn describe(copper: usize) -> String {
+    let mut remaining = copper;
+    let mut coins = vec![];
+
+    for denomination in Denomination::ALL.iter() {
+        let count = remaining / denomination.value();
+        remaining %= denomination.value();
+
+        if count > 0 {
+            coins.push(format!("{} {}", count, denomination.name()));
+        }
+    }
+
+    match coins.len() {
+        0 => "no money".to_owned(),
+        1 => coins.remove(0),
+        _ => {
+            let last = coins.pop().unwrap_or_default();
+            format!("{} and {}", coins.join(", "), last)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet() {
+        let mut wallet = Wallet::new();
+        assert_eq!(wallet.describe(), "no money");
+
+        wallet.deposit(123);
+        assert_eq!(wallet.describe(), "1 gold, 2 silver and 3 copper");
+
+        assert!(wallet.withdraw(200).is_err());
+        wallet.withdraw(23).unwrap();
+        assert_eq!(wallet.describe(), "1 gold");
+        assert_eq!(wallet.empty(), 100);
+        assert!(wallet.is_empty());
+    }
+
+    #[test]
+    fn test_parse() {
+        let args: Vec<String> = vec!["2".into(), "gold".into(), "Bob".into()];
+        assert_eq!(Denomination::parse(&args), Some((200, &args[2..])));
+
+        let args: Vec<String> = vec!["30".into(), "Bob".into()];
+        assert_eq!(Denomination::parse(&args), Some((30, &args[1..])));
+
+        let args: Vec<String> = vec!["rock".into(), "Bob".into()];
+        assert_eq!(Denomination::parse(&args), None);
+
+        let args: Vec<String> = vec!["999999999999999999".into(), "gold".into()];
+        let (copper, _) = Denomination::parse(&args).unwrap();
+        assert!(Wallet::with(1000).withdraw(copper).is_err());
+    }
+}
@@ -1,14 +1,37 @@
 use log::{error, trace, warn};
 
+use crate::core::dialogue::Consequence;
 use crate::core::entities::cache::*;
 use crate::core::entities::*;
+use crate::core::inventory::Quantity;
+use crate::core::scripting::{Event, Intent};
+use crate::core::trade::{Offer, Trade};
+use crate::core::wallet::{self, Denomination};
 use crate::core::*;
-use crate::services::{accounts::Account, db::Dynamo};
+use crate::services::{
+    accounts::Account,
+    db::Dynamo,
+    mailboxes::{Letter, Mailbox},
+    names::HeroName,
+};
 
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 type CommandOutput = Result<Vec<Update>, TCError>;
 
+/// How many times we'll roll for an unused hero name before giving up
+const NAME_ATTEMPTS: usize = 10;
+
+/// How many letters can wait in a mailbox for an offline hero
+const MAILBOX_LIMIT: usize = 50;
+
+/// The highest health percentage a hero can set their wimpy threshold to
+const MAX_WIMPY: usize = 50;
+
+/// The longest name a hero can ENGRAVE on an item
+const MAX_TITLE: usize = 32;
+
 /// Represents a command from a player, sent from the Connection actor into
 /// the shared World instance.
 #[derive(Debug)]
@@ -44,6 +67,27 @@ pub struct World {
     /// Master list of Mob templates
     pub mob_prototypes: Prototypes<MobPrototype>,
 
+    /// Heroes with an open connection
+    online: HashSet<Identifier>,
+
+    /// Global chat channels
+    pub channels: Channels,
+
+    /// Groups of mobs adventuring together
+    pub parties: Parties,
+
+    /// Trades between heroes, and requests to start them
+    pub trades: Trades,
+
+    /// Compiled NPC scripts, by prototype
+    pub scripts: Scripts,
+
+    /// NPC conversation trees, by prototype
+    pub dialogues: HashMap<String, Dialogue>,
+
+    /// Things heroes can craft, by (lowercase) name
+    pub recipes: HashMap<String, Recipe>,
+
     /// World clock
     clock: Clock,
 }
@@ -62,6 +106,13 @@ impl World {
             spaces: Cache::new(),
             item_prototypes: Prototypes::default(),
             mob_prototypes: Prototypes::default(),
+            online: HashSet::new(),
+            channels: Channels::new(),
+            parties: Parties::new(),
+            trades: Trades::new(),
+            scripts: Scripts::new(),
+            dialogues: HashMap::new(),
+            recipes: HashMap::new(),
             clock: Clock::new(1_000_000_000),
         }
     }
@@ -77,13 +128,47 @@ impl World {
         output.append(&mut self.spaces.tick(&self, &mut dice));
         output.append(&mut self.mobs.tick(&self, &mut dice));
 
+        // poison and fire kill just as surely as a blade
+        for mob in self.mobs.select(|m| m.is_dead() && !m.ghost) {
+            if let Ok(mut space) = self.spaces.get(&mob.space_id) {
+                output.append(&mut space.bury(mob, None, self));
+                output.append(&mut space.population_update(self));
+                self.spaces.insert(space);
+            }
+        }
+
+        // scripted NPCs act once the caches are unlocked
+        let scripted = self
+            .mobs
+            .select(|m| !m.is_hero() && m.is_alive() && self.scripts.has(&m.prototype));
+        for mob in scripted {
+            output.append(&mut self.script(&mob, Event::Tick));
+        }
+
+        output.append(&mut self.wander(&mut dice));
+
         output
     }
 
     /// Performs a round of melee combat
     pub fn melee(&self) -> Vec<Update> {
         let started = Instant::now();
-        let output = self.spaces.melee(&self, &mut Dice::new());
+        let mut dice = Dice::new();
+        let mut output = self.spaces.melee(&self, &mut dice);
+
+        // mobs that fell below their wimpy threshold try to run, now that the spaces are unlocked
+        while let Some(mut mob) = self.mobs.find(|m| m.fleeing) {
+            mob.fleeing = false;
+            let mob_id = mob.identifier().clone();
+            self.mobs.insert(mob);
+
+            output.push(Update::combat(&mob_id, "You panic!".to_owned()));
+            match self.flee(&mob_id, &mut dice) {
+                Ok(mut updates) => output.append(&mut updates),
+                Err(TCError::User(message)) => output.push(Update::combat(&mob_id, message)),
+                Err(e) => warn!("Auto-flee failed for {}: {:?}", mob_id, e),
+            }
+        }
 
         trace!("⚔️  {:?} - {:?}", started.elapsed(), output);
 
@@ -99,21 +184,80 @@ impl World {
     pub async fn command(&mut self, msg: Command) -> Vec<Update> {
         trace!("COMMAND - msg: {:?}", msg);
 
-        let results = match msg.phrase.verb().to_uppercase().as_ref() {
-            "LOOK" => self.look(&msg.from, msg.phrase.args().first()).await,
+        let verb = msg.phrase.verb().to_uppercase();
+
+        // doing anything but going AFK means you're at the keyboard
+        let mut updates = match verb.as_ref() {
+            "AFK" => vec![],
+            _ => self.touch(&msg.from),
+        };
+
+        let results = match verb.as_ref() {
+            "LOOK" => self.look(&msg.from, msg.phrase.args()).await,
+            "SAY" => self.say(&msg.from, msg.phrase.args()).await,
+            "TALK" => self.talk(&msg.from, msg.phrase.args()).await,
+            "QUESTS" => self.list_quests(&msg.from).await,
             "FIGHT" => self.fight(&msg.from, msg.phrase.args().first()).await,
+            "FLEE" => self.flee(&msg.from, &mut Dice::new()),
+            "WIMPY" => self.wimpy(&msg.from, msg.phrase.args().first()).await,
+            "YIELD" => self.surrender(&msg.from, msg.phrase.args().first()).await,
             "GO" => self.go(&msg.from, msg.phrase.args().first()).await,
             "INVENTORY" => self.inventory(&msg.from).await,
-            "TAKE" => self.take(&msg.from, msg.phrase.args().first()).await,
-            "DROP" => self.drop(&msg.from, msg.phrase.args().first()).await,
+            "TAKE" => self.take(&msg.from, msg.phrase.args()).await,
+            "PUT" => self.put(&msg.from, msg.phrase.args()).await,
+            "DROP" => self.drop(&msg.from, msg.phrase.args()).await,
+            "EAT" | "DRINK" | "QUAFF" | "READ" => {
+                self.consume(&msg.from, msg.phrase.verb(), msg.phrase.args().first())
+                    .await
+            }
+            "WEAR" => self.wear(&msg.from, msg.phrase.args().first()).await,
+            "WIELD" => self.wield(&msg.from, msg.phrase.args().first()).await,
+            "REMOVE" => self.remove(&msg.from, msg.phrase.args().first()).await,
+            "EQUIPMENT" => self.equipment(&msg.from).await,
+            "OPEN" => self.latch(&msg.from, "open", msg.phrase.args().first()),
+            "CLOSE" => self.latch(&msg.from, "close", msg.phrase.args().first()),
+            "LOCK" => self.latch(&msg.from, "lock", msg.phrase.args().first()),
+            "UNLOCK" => self.latch(&msg.from, "unlock", msg.phrase.args().first()),
+            "GIVE" => self.give(&msg.from, msg.phrase.args()).await,
+            "BALANCE" => self.balance(&msg.from).await,
+            "SCORE" => self.score(&msg.from).await,
+            "ENGRAVE" => self.engrave(&msg.from, msg.phrase.args()).await,
+            "CRAFT" => self.craft(&msg.from, msg.phrase.args()).await,
+            "LIST" => self.list_wares(&msg.from).await,
+            "BUY" => self.buy(&msg.from, msg.phrase.args().first()).await,
+            "SELL" => self.sell(&msg.from, msg.phrase.args().first()).await,
+            "VALUE" => self.value(&msg.from, msg.phrase.args().first()).await,
+            "LOOT" => self.loot(&msg.from, msg.phrase.args().first()).await,
+            "RESPAWN" => self.respawn(&msg.from).await,
             "REFRESH" => self.refresh(&msg.from).await,
             "TIME" => self.time(&msg.from).await,
             "SAVE" => self.save(&msg.from).await,
             "QUIT" => self.quit(&msg.from).await,
+            "CHANNELS" => self.list_channels(&msg.from).await,
+            "JOIN" => self.join(&msg.from, msg.phrase.args().first()).await,
+            "LEAVE" => self.leave(&msg.from, msg.phrase.args().first()).await,
+            "HISTORY" => self.history(&msg.from, msg.phrase.args().first()).await,
+            "MUTE" => self.mute(&msg.from, msg.phrase.args().first()).await,
+            "UNMUTE" => self.unmute(&msg.from, msg.phrase.args().first()).await,
+            "TELL" => self.tell(&msg.from, msg.phrase.args()).await,
+            "REPLY" => self.reply(&msg.from, msg.phrase.args()).await,
+            "BLOCK" => self.block(&msg.from, msg.phrase.args().first()).await,
+            "UNBLOCK" => self.unblock(&msg.from, msg.phrase.args().first()).await,
+            "GROUP" => self.group(&msg.from, msg.phrase.args()).await,
+            "PARTY" => self.party(&msg.from, msg.phrase.args()).await,
+            "FOLLOW" => self.follow(&msg.from, msg.phrase.args().first()).await,
+            "WHO" => self.who(&msg.from).await,
+            "AFK" => self.afk(&msg.from, msg.phrase.args()).await,
+            "NOTIFY" => self.notify(&msg.from, msg.phrase.args().first()).await,
+            "BARTER" => self.trade(&msg.from, msg.phrase.args()).await,
+            verb if self.channels.contains(&verb.to_lowercase()) => {
+                self.chat(&msg.from, &verb.to_lowercase(), msg.phrase.args())
+                    .await
+            }
             _ => Err(TCError::user("... What?")),
         };
 
-        let updates = match results {
+        updates.append(&mut match results {
             Ok(messages) => messages,
             Err(tce) => match tce {
                 TCError::Fatal(f) => {
@@ -126,7 +270,7 @@ impl World {
                     vec![Update::error(&msg.from, "Something went wrong!")]
                 }
             },
-        };
+        });
 
         trace!("COMMAND - updates: {:?}", updates);
 
@@ -153,7 +297,13 @@ impl World {
             None => {
                 trace!("No account found for {}", account_email);
                 // create a new hero and account because we don't have one!
-                let identifier = self.create_hero().await;
+                let identifier = match self.create_hero().await {
+                    Some(identifier) => identifier,
+                    None => {
+                        error!("No hero names left for {}!", account_email);
+                        return None;
+                    }
+                };
                 let account = Account {
                     email: account_email.clone(),
                     identifier,
@@ -185,11 +335,11 @@ impl World {
     }
 
     /// Creates a new hero from the "HERO" prototype, and puts them in the "ORIGIN" space.
-    /// If either can't be found, this will panic!
-    pub async fn create_hero(&self) -> Identifier {
+    /// If either can't be found, this will panic! Returns None if there's no name free.
+    pub async fn create_hero(&self) -> Option<Identifier> {
         let mut hero = self
             .mob_prototypes
-            .create("HERO")
+            .create("HERO", &mut Dice::new())
             .expect("Could not find HERO prototype!!");
 
         let mut origin = self
@@ -197,23 +347,30 @@ impl World {
             .get(&Identifier::origin())
             .expect("Could not load ORIGIN space!!");
 
+        let db = Dynamo::new();
+
         hero.space_id = origin.identifier().clone();
-        hero.name = format!("Hero{}", rand::thread_rng().gen::<u8>());
+        hero.name = Self::available_hero_name(&db).await?;
+        hero.active = Some(Instant::now());
         let hero_identifier = hero.identifier().clone();
 
         // add them to the local cache
         self.mobs.insert(hero.clone());
 
-        let db = crate::services::db::Dynamo::new();
         db.mobs
             .put::<Mob>(&hero)
             .await
             .expect("Failed to persist Hero!");
 
+        let hero_name = HeroName::new(&hero.name, &hero_identifier);
+        if let Err(e) = db.names.put(&hero_name).await {
+            warn!("Error registering name: {:?} => {}", hero_name, e);
+        }
+
         origin.population.add(&hero_identifier);
         self.spaces.insert(origin);
 
-        hero_identifier
+        Some(hero_identifier)
     }
 
     /// Retrieves a Mob from long term storage, inserts it into the mob cache, and adds
@@ -247,6 +404,13 @@ impl World {
         };
 
         space.population.add(hero.identifier());
+        hero.active = Some(Instant::now());
+
+        // keep the name registry current, so this hero can be found while they're offline
+        let hero_name = HeroName::new(&hero.name, &identifier);
+        if let Err(e) = db.names.put(&hero_name).await {
+            warn!("Error registering name: {:?} => {}", hero_name, e);
+        }
 
         self.mobs.insert(hero);
         self.spaces.insert(space);
@@ -254,6 +418,333 @@ impl World {
         Some(identifier)
     }
 
+    /// Picks a random hero name that isn't already registered to another hero. Handing
+    /// out a taken name would steal its registration, so this gives up instead.
+    async fn available_hero_name(db: &Dynamo) -> Option<String> {
+        for _ in 0..NAME_ATTEMPTS {
+            let name = format!("Hero{}", rand::thread_rng().gen::<u16>());
+            let taken = db
+                .names
+                .get::<HeroName>(&HeroName::normalize(&name))
+                .await
+                .is_some();
+
+            if !taken {
+                return Some(name);
+            }
+        }
+
+        None
+    }
+
+    /// Finds a hero's identifier by name, whether or not they're connected
+    async fn hero_identifier(&self, name: &str) -> Result<Identifier, TCError> {
+        if let Ok(hero) = self.find_hero(name) {
+            return Ok(hero.identifier().clone());
+        }
+
+        let db = Dynamo::new();
+        db.names
+            .get::<HeroName>(&HeroName::normalize(name))
+            .await
+            .map(|n| n.identifier)
+            .ok_or_else(|| TCError::user("There's nobody by that name."))
+    }
+
+    /// Hands over any tells that arrived while the hero was offline, and empties their mailbox
+    async fn deliver_mail(&self, mob_id: &Identifier) -> Vec<Update> {
+        let db = Dynamo::new();
+        let mailbox = match db.mailboxes.get::<Mailbox>(&mob_id.value).await {
+            Some(m) => m,
+            None => return vec![],
+        };
+
+        // make it easy to answer the most recent letter
+        if let Some(letter) = mailbox.letters.last() {
+            if let Ok(mut mob) = self.mobs.get(mob_id) {
+                mob.reply_to = Some(letter.from.clone());
+                self.mobs.insert(mob);
+            }
+        }
+
+        let output = mailbox
+            .letters
+            .iter()
+            .map(|l| Update::tell(mob_id, &format!("{} told you: {}", l.name, l.message)))
+            .collect();
+
+        // only empty the mailbox once the letters are on their way
+        db.mailboxes.delete(&mob_id.value).await;
+
+        output
+    }
+
+    /// Finds a connected hero by name (case insensitive)
+    pub fn find_hero(&self, name: &str) -> Result<Mob, TCError> {
+        self.mobs
+            .find(|m| m.is_hero() && m.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| TCError::user("There's nobody around by that name."))
+    }
+
+    /// Makes two mobs enemies. Attacking a member of a group makes enemies of every
+    /// member of that group who's in the same space.
+    pub fn provoke(&self, attacker: &mut Mob, target: &mut Mob) {
+        attacker.add_enemy(target.identifier());
+        target.add_enemy(attacker.identifier());
+
+        for member_id in self.parties.members(target.identifier()) {
+            if &member_id == target.identifier() || &member_id == attacker.identifier() {
+                continue;
+            }
+
+            if let Ok(mut member) = self.mobs.get(&member_id) {
+                if member.space_id == target.space_id {
+                    member.add_enemy(attacker.identifier());
+                    attacker.add_enemy(&member_id);
+                    self.mobs.insert(member);
+                }
+            }
+        }
+    }
+
+    /// Rewards the killer (and their group members in the same space) for a kill
+    pub fn reward(&self, killer_id: &Identifier, victim: &Mob) -> Vec<Update> {
+        let mut output = vec![];
+
+        let killer = match self.mobs.get(killer_id) {
+            Ok(k) => k,
+            Err(_) => return output,
+        };
+
+        let sharing: Vec<Mob> = self
+            .parties
+            .members(killer_id)
+            .iter()
+            .flat_map(|id| self.mobs.get(id))
+            .filter(|m| m.space_id == killer.space_id && m.is_alive())
+            .collect();
+
+        if sharing.is_empty() {
+            return output;
+        }
+
+        let experience = std::cmp::max(victim.max_health() / sharing.len(), 1);
+
+        for mut member in sharing {
+            member.experience += experience;
+            output.push(Update::info(
+                member.identifier(),
+                &format!("You gain {} experience.", experience),
+            ));
+            self.mobs.insert(member);
+        }
+
+        output
+    }
+
+    /// Returns true if any of the mob's enemies are alive and in the same space
+    fn in_combat(&self, mob: &Mob) -> bool {
+        let space = match self.spaces.get(&mob.space_id) {
+            Ok(s) => s,
+            Err(_) => return false,
+        };
+
+        mob.enemies.iter().any(|enemy_id| {
+            space.population.identifiers().contains(enemy_id)
+                && self
+                    .mobs
+                    .get(enemy_id)
+                    .map(|e| e.is_alive())
+                    .unwrap_or(false)
+        })
+    }
+
+    /// Runs a scripted mob's handler for the event, and carries out what it asks for
+    fn script(&self, mob: &Mob, event: Event) -> Vec<Update> {
+        let intents = self.scripts.run(mob, self.clock.is_night(), event);
+
+        let mut output = vec![];
+        for intent in intents {
+            match self.perform(mob.identifier(), &intent) {
+                Ok(mut updates) => output.append(&mut updates),
+                Err(e) => trace!("{} couldn't {:?}: {:?}", mob.name(), intent, e),
+            }
+        }
+
+        output
+    }
+
+    /// Carries out a scripted mob's intent, as if they'd typed a command
+    fn perform(&self, mob_id: &Identifier, intent: &Intent) -> CommandOutput {
+        let mut mob = self.living(mob_id)?;
+        let mut space = self.spaces.get(&mob.space_id)?;
+
+        match intent {
+            Intent::Say(text) => {
+                Ok(space.announce(&[mob_id], &format!("{} says, \"{}\"", mob.name(), text)))
+            }
+            Intent::Go(direction) => self.relocate(mob_id, direction),
+            Intent::SpawnItem(prototype) => {
+                let item = self
+                    .item_prototypes
+                    .create(prototype, &mut Dice::new())
+                    .ok_or_else(|| TCError::System(format!("No item prototype {:?}", prototype)))?;
+
+                let mut output =
+                    space.announce(&[], &format!("{} produces a {}.", mob.name(), item.name()));
+                space.inventory.add(item);
+                output.append(&mut space.space_update(self));
+                self.spaces.insert(space);
+
+                Ok(output)
+            }
+            Intent::Attack(name) => {
+                let mut target = space
+                    .population
+                    .identifiers()
+                    .iter()
+                    .flat_map(|id| self.mobs.get(id))
+                    .find(|m| {
+                        m.identifier() != mob_id
+                            && m.is_alive()
+                            && m.name().eq_ignore_ascii_case(name)
+                    })
+                    .ok_or_else(|| TCError::user("You don't see them here."))?;
+
+                self.provoke(&mut mob, &mut target);
+
+                let output = vec![Update::combat(
+                    target.identifier(),
+                    format!("{} attacks you!", mob.name()),
+                )];
+
+                self.mobs.insert(mob);
+                self.mobs.insert(target);
+
+                Ok(output)
+            }
+        }
+    }
+
+    /// The shortest list of directions from one space to another
+    pub fn path(&self, from: &Identifier, to: &Identifier) -> Option<Vec<String>> {
+        pathfinding::find_path(from, to, |id| match self.spaces.get(id) {
+            Ok(space) => space.exits.into_iter().collect(),
+            Err(_) => vec![],
+        })
+    }
+
+    /// Moves NPCs that wander, patrol or head home, as long as they're not busy or fighting
+    fn wander(&self, dice: &mut Dice) -> Vec<Update> {
+        let mut output = vec![];
+
+        let movers = self.mobs.select(|m| {
+            let movement = &m.movement;
+            let moves = movement.wander > 0 || !movement.patrol.is_empty() || movement.home;
+
+            moves
+                && !m.is_hero()
+                && m.is_alive()
+                && !m.is_busy()
+                && m.enemies.is_empty()
+                && !m.statuses.has(Condition::Stunned)
+        });
+
+        for mut mob in movers {
+            let mob_id = mob.identifier().clone();
+
+            let direction = if !mob.movement.patrol.is_empty() {
+                // head for the next stop on the route, moving on once we're there
+                // (a saved step can be past the end of a route that's since been shortened)
+                let stops = mob.movement.patrol.len();
+                let mut step = mob.patrol_step % stops;
+                if mob.movement.patrol[step] == mob.space_id {
+                    step = (step + 1) % stops;
+                }
+
+                if step != mob.patrol_step {
+                    mob.patrol_step = step;
+                    self.mobs.insert(mob.clone());
+                }
+
+                let stop = &mob.movement.patrol[step];
+                self.path(&mob.space_id, stop)
+                    .and_then(|route| route.first().cloned())
+            } else if dice.range(0, 100) < mob.movement.wander {
+                self.random_exit(&mob, dice)
+            } else if mob.movement.home {
+                mob.home
+                    .as_ref()
+                    .and_then(|home| self.path(&mob.space_id, home))
+                    .and_then(|route| route.first().cloned())
+            } else {
+                None
+            };
+
+            if let Some(direction) = direction {
+                match self.relocate(&mob_id, &direction) {
+                    Ok(mut updates) => output.append(&mut updates),
+                    Err(e) => trace!("{} couldn't go {}: {:?}", mob_id, direction, e),
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Picks an exit out of the mob's space, staying within their area (if they have one)
+    fn random_exit(&self, mob: &Mob, dice: &mut Dice) -> Option<String> {
+        let space = self.spaces.get(&mob.space_id).ok()?;
+
+        let mut exits: Vec<String> = space
+            .exits
+            .iter()
+            .filter(|(_, destination)| match &mob.movement.area {
+                Some(area) => self
+                    .spaces
+                    .get(destination)
+                    .map(|d| d.area.as_ref() == Some(area))
+                    .unwrap_or(false),
+                None => true,
+            })
+            .map(|(direction, _)| direction.to_owned())
+            .collect();
+
+        if exits.is_empty() {
+            return None;
+        }
+
+        // sorted, so seeded dice always pick the same way
+        exits.sort();
+        Some(exits.swap_remove(dice.range(0, exits.len())))
+    }
+
+    /// Fetches a mob, refusing if they're a ghost
+    fn living(&self, mob_id: &Identifier) -> Result<Mob, TCError> {
+        let mob = self.mobs.get(mob_id)?;
+        if mob.ghost {
+            return Err(TCError::user(
+                "You're a ghost! Your hands pass right through everything.",
+            ));
+        }
+
+        Ok(mob)
+    }
+
+    /// Removes a mob from their group, and lets the rest of the group know
+    fn leave_group(&mut self, mob_id: &Identifier) -> CommandOutput {
+        let mob = self.mobs.get(mob_id)?;
+
+        let output = self
+            .parties
+            .leave(mob_id)
+            .iter()
+            .map(|member| Update::info(member, &format!("{} left the group.", mob.name())))
+            .collect();
+
+        Ok(output)
+    }
+
     // ACTIONS! ---------------------------------------------------------------------------------
 
     async fn refresh(&self, mob_id: &Identifier) -> CommandOutput {
@@ -267,34 +758,136 @@ impl World {
         output.push(Update::space(mob_id, space.describe(&self)));
         output.push(Update::time(mob_id, &self.clock.into()));
         output.push(Update::inventory(mob_id, &mob.inventory));
+        output.push(Update::equipment(mob_id, &mob.equipment));
+        output.push(Update::wallet(mob_id, &mob.wallet));
         output.push(Update::health(mob_id, mob.health()));
+        output.append(&mut self.deliver_mail(mob_id).await);
 
         Ok(output)
     }
 
     async fn go(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
-        let mut output = vec![];
-
         // parse the direction
         let direction_src = arg.ok_or_else(|| TCError::user("Which way?"))?;
         let direction = direction_src.to_lowercase();
 
+        let mob = self.mobs.get(mob_id)?;
+        let from_space_id = mob.space_id.clone();
+
+        self.free_to_leave(&mob)?;
+
+        let mut output = self.relocate(mob_id, &direction)?;
+
+        // heavy loads slow you down
         let mut mob = self.mobs.get(mob_id)?;
-        let mut current_space = self.spaces.get(&mob.space_id)?;
+        if let Some(delay) = mob.encumbrance().delay() {
+            mob.busy(Doing::Moving, delay);
+            self.mobs.insert(mob);
+        }
+
+        // followers come along, as long as they were in the same space as whoever they follow
+        let mut leaders = vec![mob_id.clone()];
+        while let Some(leader_id) = leaders.pop() {
+            let leader = self.mobs.get(&leader_id)?;
+            let from_space = self.spaces.get(&from_space_id)?;
+
+            for follower_id in from_space.population.identifiers() {
+                let follower = match self.mobs.get(follower_id) {
+                    Ok(m) => m,
+                    Err(_) => continue,
+                };
+
+                // anyone fighting or busy stays put
+                if follower.following.as_ref() != Some(&leader_id)
+                    || self.free_to_leave(&follower).is_err()
+                {
+                    continue;
+                }
+
+                if let Ok(mut updates) = self.relocate(follower_id, &direction) {
+                    output.push(Update::info(
+                        follower_id,
+                        &format!("You follow {}.", leader.name()),
+                    ));
+                    output.append(&mut updates);
+                    leaders.push(follower_id.clone());
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Checks the mob isn't fighting or too busy to walk away
+    fn free_to_leave(&self, mob: &Mob) -> Result<(), TCError> {
+        if self.in_combat(mob) {
+            return Err(TCError::user(
+                "You can't just walk away from a fight! Try to FLEE.",
+            ));
+        }
+
+        if mob.doing() == &Doing::Moving {
+            return Err(TCError::user("You're still catching your breath."));
+        }
+
+        if let Doing::Crafting(_) = mob.doing() {
+            return Err(TCError::user("You're in the middle of crafting."));
+        }
+
+        Ok(())
+    }
+
+    /// Moves a mob through the exit in the given direction
+    fn relocate(&self, mob_id: &Identifier, direction: &str) -> CommandOutput {
+        let mob = self.mobs.get(mob_id)?;
+        let current_space = self.spaces.get(&mob.space_id)?;
 
         // get the new space ID based on the direction
         let new_space_id = current_space
             .exits
-            .get(&direction)
+            .get(direction)
             .ok_or_else(|| TCError::user("You can't go that way."))?
             .clone();
 
-        let mut new_space = self.spaces.get(&new_space_id)?;
+        let new_space = self.spaces.get(&new_space_id)?;
+
+        // use the exit that leads back to where they came from to describe where they arrived from
+        let arrived_from = new_space
+            .exits
+            .iter()
+            .find(|(_, id)| *id == current_space.identifier())
+            .map(|(d, _)| d.to_owned());
+
+        self.transport(
+            mob_id,
+            &new_space_id,
+            &departure(mob.name(), direction),
+            &arrival(mob.name(), arrived_from.as_deref()),
+        )
+    }
+
+    /// Moves a mob into another space, letting everyone know they've come and gone
+    fn transport(
+        &self,
+        mob_id: &Identifier,
+        new_space_id: &Identifier,
+        departure: &str,
+        arrival: &str,
+    ) -> CommandOutput {
+        let mut output = vec![];
+
+        let mut mob = self.mobs.get(mob_id)?;
+        let mut current_space = self.spaces.get(&mob.space_id)?;
+        let from_space_id = mob.space_id.clone();
+        let mut new_space = self.spaces.get(new_space_id)?;
 
         // remove them from the old space, add them to the new space
         current_space.population.remove(mob_id);
         new_space.population.add(mob_id);
 
+        output.append(&mut current_space.announce(&[], departure));
+        output.append(&mut new_space.announce(&[mob_id], arrival));
+
         self.spaces.insert(current_space);
         self.spaces.insert(new_space);
 
@@ -302,21 +895,168 @@ impl World {
         mob.space_id = new_space_id.to_owned();
         // reset the enemies list
         mob.enemies = vec![];
+        mob.yielding_to = None;
+        let hero_name = if mob.is_hero() {
+            Some(mob.name().to_owned())
+        } else {
+            None
+        };
         self.mobs.insert(mob); // save the updated mob
 
-        let new_space = self.spaces.get(&new_space_id)?;
+        let current_space = self.spaces.get(&from_space_id)?;
+        let new_space = self.spaces.get(new_space_id)?;
 
         // craft the update
-        output.push(Update::space(mob_id, new_space.describe(&self)));
-        output.push(Update::population(
-            mob_id,
-            new_space.population.names(&self),
-        ));
+        output.push(Update::space(mob_id, new_space.describe(self)));
+        output.append(&mut current_space.population_update(self));
+        output.append(&mut new_space.population_update(self));
+
+        // scripted locals notice heroes arriving (but not each other, so they can't ping-pong)
+        if let Some(name) = hero_name {
+            for local in new_space
+                .population
+                .identifiers()
+                .iter()
+                .filter(|id| *id != mob_id)
+                .flat_map(|id| self.mobs.get(id))
+            {
+                output.append(&mut self.script(&local, Event::Enter(name.clone())));
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Tries to run from a fight through a random exit
+    fn flee(&self, mob_id: &Identifier, dice: &mut Dice) -> CommandOutput {
+        let mob = self.mobs.get(mob_id)?;
+        if !self.in_combat(&mob) {
+            return Err(TCError::user("You're not fighting anyone."));
+        }
+
+        let space = self.spaces.get(&mob.space_id)?;
+
+        // sorted, so seeded dice always pick the same way
+        let mut exits: Vec<&String> = space.exits.keys().collect();
+        exits.sort();
+
+        if exits.is_empty() {
+            return Err(TCError::user("There's nowhere to run!"));
+        }
+
+        if !combat::flee_roll(&mob, dice) {
+            let mut output = vec![Update::combat(
+                mob_id,
+                "You try to flee, but can't get away!".to_owned(),
+            )];
+            output
+                .append(&mut space.announce(&[mob_id], &format!("{} tries to flee!", mob.name())));
+            return Ok(output);
+        }
+
+        let direction = exits[dice.range(0, exits.len())].to_owned();
+
+        let mut output = vec![Update::combat(mob_id, format!("You flee {}!", direction))];
+        output.append(&mut self.relocate(mob_id, &direction)?);
 
         Ok(output)
     }
 
-    async fn look(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+    /// Marks a hero as online when their connection opens, and lets everyone nearby
+    /// (and anyone who asked for notices) know they've arrived
+    pub fn connect(&mut self, mob_id: &Identifier) -> Vec<Update> {
+        self.online.insert(mob_id.to_owned());
+
+        let mut output = vec![];
+
+        if let Ok(mob) = self.mobs.get(mob_id) {
+            if let Ok(space) = self.spaces.get(&mob.space_id) {
+                let message = format!("{} appears out of thin air.", mob.name());
+                output.append(&mut space.announce(&[mob_id], &message));
+                output.append(&mut space.population_update(&self));
+            }
+
+            output.append(&mut self.notice(&mob, &format!("{} has arrived.", mob.name())));
+        }
+
+        output
+    }
+
+    /// Cleans up after a hero's connection closes, called by the Connection actor.
+    /// Heroes who QUIT are already gone, and have said their goodbyes.
+    pub fn disconnect(&mut self, mob_id: &Identifier) -> Vec<Update> {
+        self.online.remove(mob_id);
+        self.channels.leave_all(mob_id);
+
+        let mob = match self.mobs.get(mob_id) {
+            Ok(mob) => mob,
+            Err(_) => return vec![],
+        };
+
+        let mut output = self.call_off_trade(&mob);
+        output.append(&mut self.notice(&mob, &format!("{} has left.", mob.name())));
+        output
+    }
+
+    /// Cancels any trade (or request to trade) a departing hero was part of,
+    /// so their partner isn't left waiting
+    fn call_off_trade(&mut self, mob: &Mob) -> Vec<Update> {
+        let trade = match self.trades.cancel(mob.identifier()) {
+            Some(trade) => trade,
+            None => return vec![],
+        };
+
+        trade
+            .counter_offer(mob.identifier())
+            .map(|partner| {
+                Update::info(
+                    &partner.identifier,
+                    &format!("{} has gone, so the trade is off.", mob.name()),
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Tells every connected hero who asked for login/logout notices (except the hero in question)
+    fn notice(&self, about: &Mob, message: &str) -> Vec<Update> {
+        self.mobs
+            .select(|m| {
+                self.online.contains(m.identifier())
+                    && m.notify
+                    && m.identifier() != about.identifier()
+            })
+            .iter()
+            .map(|m| Update::info(m.identifier(), message))
+            .collect()
+    }
+
+    /// Notes that the hero is at the keyboard, welcoming them back if they were away
+    fn touch(&self, mob_id: &Identifier) -> Vec<Update> {
+        let mut mob = match self.mobs.get(mob_id) {
+            Ok(mob) => mob,
+            Err(_) => return vec![],
+        };
+
+        mob.active = Some(Instant::now());
+        let returning = mob.away.take().is_some();
+        self.mobs.insert(mob);
+
+        if returning {
+            vec![Update::info(mob_id, "Welcome back!")]
+        } else {
+            vec![]
+        }
+    }
+
+    async fn look(&mut self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        if let [preposition, container] = args {
+            if preposition.eq_ignore_ascii_case("in") {
+                return self.look_in(mob_id, container);
+            }
+        }
+
+        let arg = args.first();
         let mob = self.mobs.get(mob_id)?;
         let space = self.spaces.get(&mob.space_id)?;
 
@@ -344,55 +1084,89 @@ impl World {
             }
         }
 
+        for item in mob.inventory.items().iter().chain(mob.equipment.items()) {
+            if item.name() == name {
+                let update = Update::item(mob_id, item.describe(self));
+                return Ok(vec![update]);
+            }
+        }
+
         Err(TCError::user("You don't see that here."))
     }
 
-    async fn take(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
-        let mut output = vec![];
-
-        let item_name = arg.ok_or_else(|| TCError::user("Take what?"))?;
-
+    /// Lists what's inside a container, with each item clickable to take it out
+    fn look_in(&self, mob_id: &Identifier, name: &str) -> CommandOutput {
         let mut mob = self.mobs.get(mob_id)?;
         let mut space = self.spaces.get(&mob.space_id)?;
 
-        if let Some(item) = space.inventory.remove(item_name) {
-            mob.inventory.add(item);
-        } else {
-            return Err(TCError::user("You don't see that."));
-        }
+        let container = container_mut(&mut mob.inventory, &mut space.inventory, name)?;
+        container.check_open()?;
 
-        output.push(Update::info(
-            mob_id,
-            &format!("You took the {}.", item_name),
-        ));
+        let mut clicks = HashMap::new();
+        let text = if container.contents.is_empty() {
+            format!("The {} is empty.", name)
+        } else {
+            let names: Vec<String> = container
+                .contents
+                .items()
+                .iter()
+                .map(|i| {
+                    clicks.insert(
+                        i.name().to_owned(),
+                        format!("take {} from {}", i.name(), name),
+                    );
+                    format!("[[{}]]", i.name())
+                })
+                .collect();
+
+            format!("The {} holds {}.", name, names.join(", "))
+        };
 
-        output.push(Update::inventory(mob_id, &mob.inventory));
-        output.push(Update::space(mob_id, space.describe(&self)));
+        Ok(vec![Update::item(mob_id, Markup { text, clicks })])
+    }
 
-        self.mobs.insert(mob);
-        self.spaces.insert(space);
+    async fn take(&mut self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let (quantity, args) = Quantity::parse(args);
 
-        Ok(output)
-    }
+        if let [item_name, preposition, container] = args {
+            if preposition.eq_ignore_ascii_case("from") {
+                return self.take_from(mob_id, item_name, quantity, container);
+            }
+        }
 
-    async fn drop(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
         let mut output = vec![];
 
-        let item_name = arg.ok_or_else(|| TCError::user("Take what?"))?;
+        let item_name = args.first().ok_or_else(|| TCError::user("Take what?"))?;
 
-        let mut mob = self.mobs.get(mob_id)?;
+        let mut mob = self.living(mob_id)?;
         let mut space = self.spaces.get(&mob.space_id)?;
 
-        if let Some(item) = mob.inventory.remove(item_name) {
-            space.inventory.add(item);
+        let items = space
+            .inventory
+            .take(item_name, quantity)
+            .ok_or_else(|| TCError::user("You don't see that."))?;
+
+        let room = if items.iter().any(|i| i.is_corpse()) {
+            Err(TCError::user("It's too heavy to carry. Try to LOOT it."))
         } else {
-            return Err(TCError::user("You don't have that."));
+            mob.can_carry(&items)
+        };
+
+        if let Err(e) = room {
+            for item in items {
+                space.inventory.add(item);
+            }
+            return Err(e);
         }
 
-        output.push(Update::info(
-            mob_id,
-            &format!("You dropped {:?}.", item_name),
-        ));
+        let before = mob.encumbrance();
+        let taken = label(&items);
+        for item in items {
+            mob.inventory.add(item);
+        }
+
+        output.push(Update::info(mob_id, &format!("You took {}.", taken)));
+        output.extend(burden(&mob, before));
 
         output.push(Update::inventory(mob_id, &mob.inventory));
         output.push(Update::space(mob_id, space.describe(&self)));
@@ -403,86 +1177,2728 @@ impl World {
         Ok(output)
     }
 
-    async fn inventory(&self, mob_id: &Identifier) -> CommandOutput {
-        let mob = self.mobs.get(mob_id)?;
-        let inventory = &mob.inventory;
-        let update = Update::inventory(mob_id, inventory);
+    fn take_from(
+        &self,
+        mob_id: &Identifier,
+        item_name: &str,
+        quantity: Quantity,
+        name: &str,
+    ) -> CommandOutput {
+        let mut mob = self.living(mob_id)?;
+        let mut space = self.spaces.get(&mob.space_id)?;
 
-        Ok(vec![update])
+        let before = mob.encumbrance();
+
+        let container = container_mut(&mut mob.inventory, &mut space.inventory, name)?;
+        container.check_open()?;
+
+        let items = container
+            .contents
+            .take(item_name, quantity)
+            .ok_or_else(|| TCError::User(format!("There's no {} in the {}.", item_name, name)))?;
+
+        // out of the container first, so a bag you're carrying doesn't count twice
+        if let Err(e) = mob.can_carry(&items) {
+            let container = container_mut(&mut mob.inventory, &mut space.inventory, name)?;
+            for item in items {
+                container.contents.add(item);
+            }
+            return Err(e);
+        }
+
+        let taken = label(&items);
+        for item in items {
+            mob.inventory.add(item);
+        }
+
+        let burdened = burden(&mob, before);
+        let mut output = self.rummage(
+            mob,
+            space,
+            &format!("You take {} from the {}.", taken, name),
+            "takes something from",
+            name,
+        )?;
+        output.extend(burdened);
+
+        Ok(output)
     }
+    /// PUT [count|ALL] <item> IN <container>
+    /// PUT <item> IN <container>
+    async fn put(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let (quantity, args) = Quantity::parse(args);
+        let (item_name, name) = match args {
+            [item_name, preposition, name]
+                if preposition.eq_ignore_ascii_case("in")
+                    || preposition.eq_ignore_ascii_case("into") =>
+            {
+                (item_name, name)
+            }
+            _ => return Err(TCError::user("Put what in what?")),
+        };
 
-    async fn fight(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
-        let target_name = arg.ok_or_else(|| TCError::user("Fight who?"))?;
+        let mut mob = self.living(mob_id)?;
+        let mut space = self.spaces.get(&mob.space_id)?;
+        let before = mob.encumbrance();
+
+        let items = mob
+            .inventory
+            .take(item_name, quantity)
+            .ok_or_else(|| TCError::user("You don't have that."))?;
+
+        // the items are out of the way, so a bag can't end up inside itself
+        let count = items.iter().map(|i| i.quantity).sum();
+        let room = container_mut(&mut mob.inventory, &mut space.inventory, name)
+            .and_then(|c| c.can_hold(count));
+        if let Err(e) = room {
+            for item in items {
+                mob.inventory.add(item);
+            }
+            return Err(e);
+        }
 
-        let mob = self.mobs.get(mob_id)?;
-        let space = self.spaces.get(&mob.space_id)?;
+        let put = label(&items);
+        let container = container_mut(&mut mob.inventory, &mut space.inventory, name)?;
+        for item in items {
+            container.contents.add(item);
+        }
 
-        for local_mob in &space.population.mobs {
-            let mut target_mob = self.mobs.get(&local_mob)?;
-            // add the enemy if the name matches and it isn't yourself!
-            if target_mob.name() == target_name && target_mob.identifier() != mob.identifier() {
-                let mut player = mob;
+        let burdened = burden(&mob, before);
+        let mut output = self.rummage(
+            mob,
+            space,
+            &format!("You put {} in the {}.", put, name),
+            "puts something in",
+            name,
+        )?;
+        output.extend(burdened);
 
-                // both mobs become enemies!
-                player.add_enemy(target_mob.identifier());
-                target_mob.add_enemy(player.identifier());
+        Ok(output)
+    }
 
-                let mut output = vec![];
+    /// Opens, closes, locks or unlocks a container
+    fn latch(&self, mob_id: &Identifier, verb: &str, arg: Option<&String>) -> CommandOutput {
+        let name = arg.ok_or_else(|| TCError::User(format!("What do you want to {}?", verb)))?;
 
-                output.push(Update::combat(
-                    player.identifier(),
-                    format!("You attack {}!", target_name),
-                ));
+        let mut mob = self.living(mob_id)?;
+        let mut space = self.spaces.get(&mob.space_id)?;
 
-                output.push(Update::combat(
-                    target_mob.identifier(),
-                    format!("{} attacks you!", player.name()),
-                ));
+        let keyring = mob.inventory.clone();
+        let container = container_mut(&mut mob.inventory, &mut space.inventory, name)?;
 
-                self.mobs.insert(player);
-                self.mobs.insert(target_mob);
+        let message = match verb {
+            "open" if container.open(&keyring)? => {
+                format!("You unlock the {} and open it.", name)
+            }
+            "close" => {
+                container.close()?;
+                format!("You close the {}.", name)
+            }
+            "lock" => {
+                container.lock(&keyring)?;
+                format!("You lock the {}.", name)
+            }
+            "unlock" => {
+                container.unlock(&keyring)?;
+                format!("You unlock the {}.", name)
+            }
+            _ => format!("You open the {}.", name),
+        };
 
-                return Ok(output);
+        self.rummage(mob, space, &message, &format!("{}s", verb), name)
+    }
+
+    /// Saves a mob and their space after they've been through a container, and lets
+    /// everyone else there know (eg: "Hero12 opens the chest.")
+    fn rummage(
+        &self,
+        mob: Mob,
+        space: Space,
+        message: &str,
+        action: &str,
+        container: &str,
+    ) -> CommandOutput {
+        let mut output = vec![
+            Update::info(mob.identifier(), message),
+            Update::inventory(mob.identifier(), &mob.inventory),
+        ];
+        output.append(&mut space.announce(
+            &[mob.identifier()],
+            &format!("{} {} the {}.", mob.name(), action, container),
+        ));
+
+        self.mobs.insert(mob);
+        self.spaces.insert(space);
+
+        Ok(output)
+    }
+
+    async fn drop(&mut self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let mut output = vec![];
+
+        let (quantity, args) = Quantity::parse(args);
+        let item_name = args.first().ok_or_else(|| TCError::user("Drop what?"))?;
+
+        let mut mob = self.living(mob_id)?;
+        let mut space = self.spaces.get(&mob.space_id)?;
+        let before = mob.encumbrance();
+
+        let items = mob
+            .inventory
+            .take(item_name, quantity)
+            .ok_or_else(|| TCError::user("You don't have that."))?;
+
+        let dropped = label(&items);
+        for item in items {
+            space.inventory.add(item);
+        }
+
+        output.push(Update::info(mob_id, &format!("You dropped {}.", dropped)));
+        output.extend(burden(&mob, before));
+
+        output.push(Update::inventory(mob_id, &mob.inventory));
+        output.push(Update::space(mob_id, space.describe(&self)));
+
+        self.mobs.insert(mob);
+        self.spaces.insert(space);
+
+        Ok(output)
+    }
+
+    /// Uses up a consumable with EAT, DRINK, QUAFF or READ, applying its effects
+    async fn consume(
+        &self,
+        mob_id: &Identifier,
+        verb: &str,
+        arg: Option<&String>,
+    ) -> CommandOutput {
+        let verb = verb.to_lowercase();
+        let item_name =
+            arg.ok_or_else(|| TCError::User(format!("What do you want to {}?", verb)))?;
+
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+
+        let item = mob
+            .inventory
+            .remove(item_name)
+            .ok_or_else(|| TCError::user("You don't have that."))?;
+
+        let consumable = match &item.consumable {
+            Some(c) if c.kind.accepts(&verb) => c.clone(),
+            _ => {
+                mob.inventory.add(item);
+                return Err(TCError::User(format!("You can't {} that.", verb)));
+            }
+        };
+
+        // a scroll to nowhere shouldn't get used up
+        for effect in &consumable.effects {
+            if let ItemEffect::Teleport(space_id) = effect {
+                if self.spaces.get(space_id).is_err() {
+                    return Err(TCError::System(format!(
+                        "{} teleports to missing space {}",
+                        item.prototype, space_id
+                    )));
+                }
             }
         }
 
-        Err(TCError::user("You don't see them here."))
+        let mut output = vec![Update::info(
+            mob_id,
+            &format!("You {} the {}.", verb, item.name()),
+        )];
+        output.append(&mut space.announce(
+            &[mob_id],
+            &format!("{} {}s a {}.", mob.name(), verb, item.name()),
+        ));
+
+        let mut destination = None;
+        for effect in consumable.effects {
+            let restore = match effect {
+                ItemEffect::Heal(hp) => Restore::Health(hp),
+                ItemEffect::Antidote(hp) => Restore::Antidote(hp),
+                ItemEffect::Restore => Restore::Max,
+                ItemEffect::Bless { potency, ticks } => {
+                    let blessing = Status {
+                        condition: Condition::Blessed,
+                        ticks,
+                        potency,
+                    };
+                    output.append(&mut mob.afflict(None, blessing));
+                    continue;
+                }
+                ItemEffect::Teleport(space_id) => {
+                    destination = Some(space_id);
+                    continue;
+                }
+            };
+
+            let action = Action::new(mob_id, mob_id, Effect::Heal(restore));
+            output.append(&mut mob.act(action, self));
+        }
+
+        output.push(Update::inventory(mob_id, &mob.inventory));
+        let name = mob.name().to_owned();
+        self.mobs.insert(mob);
+
+        // teleporting goes last, once everything else has taken hold
+        if let Some(space_id) = destination {
+            output.append(&mut self.transport(
+                mob_id,
+                &space_id,
+                &format!("{} vanishes in a puff of smoke!", name),
+                &format!("{} appears in a puff of smoke!", name),
+            )?);
+        }
+
+        Ok(output)
     }
 
-    async fn time(&self, mob_id: &Identifier) -> CommandOutput {
-        Ok(vec![Update::time(mob_id, &self.clock.into())])
+    async fn wear(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let item_name = arg.ok_or_else(|| TCError::user("Wear what?"))?;
+        self.equip(mob_id, item_name, false)
     }
 
-    async fn save(&self, mob_id: &Identifier) -> CommandOutput {
-        let mob = self.mobs.get(mob_id)?;
-        let db = crate::services::db::Dynamo::new();
+    async fn wield(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let item_name = arg.ok_or_else(|| TCError::user("Wield what?"))?;
+        self.equip(mob_id, item_name, true)
+    }
 
-        match db.mobs.put(&mob).await {
-            Ok(_) => Ok(vec![Update::info(mob_id, "Saved!")]),
-            Err(e) => {
-                warn!("db.mobs.put ERROR: {}", e);
-                Err(TCError::user("Something went wrong ..."))
+    /// Moves an item from the inventory into its slot, swapping out anything already
+    /// there. Weapons are wielded, and everything else is worn.
+    fn equip(&self, mob_id: &Identifier, item_name: &str, wield: bool) -> CommandOutput {
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+
+        let mut item = mob
+            .inventory
+            .remove(item_name)
+            .ok_or_else(|| TCError::user("You don't have that."))?;
+
+        let (verb, verbs) = if wield {
+            ("wield", "wields")
+        } else {
+            ("wear", "wears")
+        };
+
+        match item.slot {
+            Some(Slot::Wield) if wield => {}
+            Some(slot) if !wield && slot != Slot::Wield => {}
+            _ => {
+                mob.inventory.add(item);
+                return Err(TCError::User(format!("You can't {} that.", verb)));
             }
         }
+
+        if matches!(&item.bound_to, Some(owner) if owner != mob_id) {
+            return Err(TCError::user("It's bound to someone else."));
+        }
+
+        let mut output = vec![];
+
+        if item.binds && item.bound_to.is_none() {
+            item.bound_to = Some(mob_id.to_owned());
+            output.push(Update::info(
+                mob_id,
+                &format!("The {} binds itself to you.", item.name()),
+            ));
+        }
+
+        if let Some(previous) = mob.equipment.equip(item) {
+            output.push(Update::info(
+                mob_id,
+                &format!("You remove the {}.", previous.name()),
+            ));
+            mob.inventory.add(previous);
+        }
+
+        // swapping out a constitution bonus can't leave the Mob with more health than they can hold
+        mob.hp = std::cmp::min(mob.hp, mob.max_health());
+
+        output.push(Update::info(
+            mob_id,
+            &format!("You {} the {}.", verb, item_name),
+        ));
+        output.push(Update::inventory(mob_id, &mob.inventory));
+        output.push(Update::equipment(mob_id, &mob.equipment));
+        output.push(Update::health(mob_id, mob.health()));
+        output.append(&mut space.announce(
+            &[mob_id],
+            &format!("{} {} the {}.", mob.name(), verbs, item_name),
+        ));
+
+        self.mobs.insert(mob);
+
+        Ok(output)
     }
 
-    async fn quit(&self, mob_id: &Identifier) -> CommandOutput {
-        // fetch the affected entities
+    async fn remove(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let item_name = arg.ok_or_else(|| TCError::user("Remove what?"))?;
+
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+
+        let item = mob
+            .equipment
+            .remove(item_name)
+            .ok_or_else(|| TCError::user("You aren't wearing that."))?;
+        mob.inventory.add(item);
+
+        // losing a constitution bonus can't leave the Mob with more health than they can hold
+        mob.hp = std::cmp::min(mob.hp, mob.max_health());
+
+        let mut output = vec![
+            Update::info(mob_id, &format!("You remove the {}.", item_name)),
+            Update::inventory(mob_id, &mob.inventory),
+            Update::equipment(mob_id, &mob.equipment),
+            Update::health(mob_id, mob.health()),
+        ];
+        output.append(&mut space.announce(
+            &[mob_id],
+            &format!("{} removes the {}.", mob.name(), item_name),
+        ));
+
+        self.mobs.insert(mob);
+
+        Ok(output)
+    }
+
+    async fn equipment(&self, mob_id: &Identifier) -> CommandOutput {
         let mob = self.mobs.get(mob_id)?;
+
+        let mut output = vec![Update::equipment(mob_id, &mob.equipment)];
+        if mob.equipment.is_empty() {
+            output.push(Update::info(mob_id, "You aren't wearing anything special."));
+        }
+
+        Ok(output)
+    }
+
+    async fn loot(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let name = arg.map(|a| a.as_str()).unwrap_or("corpse");
+
+        let mut mob = self.living(mob_id)?;
         let mut space = self.spaces.get(&mob.space_id)?;
 
-        // remove the mob from the population of the space
-        space.population.remove(mob.identifier());
+        if !space.inventory.contains(name) {
+            return Err(TCError::user("You don't see that here."));
+        }
+
+        // pick the first one that still has something in it
+        let container = space
+            .inventory
+            .find_mut(|i| i.name() == name && !(i.contents.is_empty() && i.coins.is_empty()))
+            .ok_or_else(|| TCError::user("There's nothing left to take."))?;
+
+        // corpses are always open, but chests and the like have to be opened (or unlocked) first
+        container.check_open()?;
+        mob.can_carry(container.contents.items())?;
+
+        let before = mob.encumbrance();
+        let looted = container.contents.drain();
+        let mut names: Vec<String> = looted.iter().map(|i| i.label()).collect();
+        for item in looted {
+            mob.inventory.add(item);
+        }
+
+        let coins = container.coins.empty();
+        if coins > 0 {
+            names.push(wallet::describe(coins));
+            mob.wallet.deposit(coins);
+        }
+
+        let mut output = vec![
+            Update::info(mob_id, &format!("You take {}.", names.join(", "))),
+            Update::inventory(mob_id, &mob.inventory),
+            Update::wallet(mob_id, &mob.wallet),
+        ];
+        output.extend(burden(&mob, before));
+        output
+            .append(&mut space.announce(&[mob_id], &format!("{} loots the {}.", mob.name(), name)));
 
-        // save the space
+        self.mobs.insert(mob);
         self.spaces.insert(space);
 
-        // axe the mob from the cache
-        self.mobs.remove(mob_id);
+        Ok(output)
+    }
 
-        // say buh-bye!
-        Ok(vec![Update::info(mob_id, "See you later!")])
+    /// Hands over money (eg: "30 to Bob", or "2 gold Bob") or items (eg: "rock to Bob")
+    async fn give(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        if let Some((copper, rest)) = Denomination::parse(args) {
+            if let Some(target_name) = recipient(args, rest) {
+                return self.give_money(mob_id, copper, target_name);
+            }
+        }
 
-        // TODO: Figure out how to close the connection!!
+        let (quantity, rest) = Quantity::parse(args);
+        let (item_name, target_name) = match rest {
+            [item, to, name] if to.eq_ignore_ascii_case("to") => (item, name),
+            [item, name] => (item, name),
+            _ => return Err(TCError::user("Give what to whom?")),
+        };
+
+        self.give_items(mob_id, item_name, quantity, target_name)
+    }
+
+    /// Finds someone else in the space to hand things to
+    fn recipient_here(
+        &self,
+        space: &Space,
+        mob_id: &Identifier,
+        name: &str,
+    ) -> Result<Mob, TCError> {
+        space
+            .population
+            .identifiers()
+            .iter()
+            .filter(|id| *id != mob_id)
+            .flat_map(|id| self.mobs.get(id))
+            .find(|m| m.name() == name)
+            .ok_or_else(|| TCError::user("You don't see them here."))
+    }
+
+    fn give_money(&self, mob_id: &Identifier, copper: usize, target_name: &str) -> CommandOutput {
+        if copper == 0 {
+            return Err(TCError::user("Give how much?"));
+        }
+
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+        let mut target = self.recipient_here(&space, mob_id, target_name)?;
+
+        mob.wallet.withdraw(copper)?;
+        target.wallet.deposit(copper);
+
+        let amount = wallet::describe(copper);
+        let mut output = vec![
+            Update::info(
+                mob_id,
+                &format!("You give {} to {}.", amount, target.name()),
+            ),
+            Update::wallet(mob_id, &mob.wallet),
+            Update::info(
+                target.identifier(),
+                &format!("{} gives you {}.", mob.name(), amount),
+            ),
+            Update::wallet(target.identifier(), &target.wallet),
+        ];
+        output.append(&mut space.announce(
+            &[mob_id, target.identifier()],
+            &format!("{} gives {} some money.", mob.name(), target.name()),
+        ));
+
+        self.mobs.insert(mob);
+        self.mobs.insert(target);
+
+        Ok(output)
+    }
+
+    fn give_items(
+        &self,
+        mob_id: &Identifier,
+        item_name: &str,
+        quantity: Quantity,
+        target_name: &str,
+    ) -> CommandOutput {
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+        let mut target = self.recipient_here(&space, mob_id, target_name)?;
+
+        let items = mob
+            .inventory
+            .take(item_name, quantity)
+            .ok_or_else(|| TCError::user("You don't have that."))?;
+        check_unbound(&items)?;
+
+        target
+            .can_carry(&items)
+            .map_err(|_| TCError::User(format!("{} can't carry that.", target.name())))?;
+
+        let before = (mob.encumbrance(), target.encumbrance());
+        let given = label(&items);
+        for item in items {
+            target.inventory.add(item);
+        }
+
+        let mut output = vec![Update::info(
+            mob_id,
+            &format!("You give {} to {}.", given, target.name()),
+        )];
+        output.extend(burden(&mob, before.0));
+        output.push(Update::inventory(mob_id, &mob.inventory));
+        output.push(Update::info(
+            target.identifier(),
+            &format!("{} gives you {}.", mob.name(), given),
+        ));
+        output.extend(burden(&target, before.1));
+        output.push(Update::inventory(target.identifier(), &target.inventory));
+        output.append(&mut space.announce(
+            &[mob_id, target.identifier()],
+            &format!("{} gives {} {}.", mob.name(), target.name(), given),
+        ));
+
+        self.mobs.insert(mob);
+        self.mobs.insert(target);
+
+        Ok(output)
+    }
+
+    async fn balance(&self, mob_id: &Identifier) -> CommandOutput {
+        let mob = self.mobs.get(mob_id)?;
+
+        Ok(vec![
+            Update::wallet(mob_id, &mob.wallet),
+            Update::info(mob_id, &format!("You have {}.", mob.wallet.describe())),
+        ])
+    }
+
+    /// Finds a shopkeeper willing to trade with the mob in the space
+    fn shopkeeper(&self, space: &Space, mob_id: &Identifier) -> Result<Mob, TCError> {
+        let keeper = space
+            .population
+            .identifiers()
+            .iter()
+            .filter(|id| *id != mob_id)
+            .flat_map(|id| self.mobs.get(id))
+            .find(|m| m.shop.is_some() && m.is_alive())
+            .ok_or_else(|| TCError::user("There's no shop here."))?;
+
+        if keeper.enemies.contains(mob_id) {
+            return Err(TCError::User(format!(
+                "{} won't trade with you.",
+                keeper.name()
+            )));
+        }
+
+        Ok(keeper)
+    }
+
+    async fn list_wares(&self, mob_id: &Identifier) -> CommandOutput {
+        let mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+        let keeper = self.shopkeeper(&space, mob_id)?;
+        let shop = keeper.shop.clone().unwrap_or_default();
+
+        let mut lines = vec![];
+        let mut clicks = HashMap::new();
+
+        for ware in &shop.wares {
+            let prototype = &ware.restock.name;
+            let item = keeper
+                .inventory
+                .items()
+                .iter()
+                .find(|i| &i.prototype == prototype);
+
+            if let (Some(item), Some(price)) = (item, shop.asking_price(prototype, &mob)) {
+                lines.push(format!(
+                    "[[{}]]: {} ({} left)",
+                    item.name(),
+                    wallet::describe(price),
+                    keeper.inventory.count(prototype)
+                ));
+                clicks.insert(item.name().to_owned(), format!("buy {}", item.name()));
+            }
+        }
+
+        if lines.is_empty() {
+            return Ok(vec![Update::info(
+                mob_id,
+                &format!("{} has nothing for sale right now.", keeper.name()),
+            )]);
+        }
+
+        let text = format!("{} has for sale:\n{}", keeper.name(), lines.join("\n"));
+        Ok(vec![Update::item(mob_id, Markup { text, clicks })])
+    }
+
+    async fn buy(&self, mob_id: &Identifier, item_name: Option<&String>) -> CommandOutput {
+        let item_name = item_name.ok_or_else(|| TCError::user("Buy what?"))?;
+
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+        let mut keeper = self.shopkeeper(&space, mob_id)?;
+        let shop = keeper.shop.clone().unwrap_or_default();
+
+        let item = keeper.inventory.remove(item_name).ok_or_else(|| {
+            TCError::User(format!("{} doesn't have any of those.", keeper.name()))
+        })?;
+
+        let price = shop
+            .asking_price(&item.prototype, &mob)
+            .ok_or_else(|| TCError::user("That isn't for sale."))?;
+
+        mob.can_carry(std::slice::from_ref(&item))?;
+        mob.wallet
+            .withdraw(price)
+            .map_err(|_| TCError::User(format!("You can't afford {}.", item.label())))?;
+        keeper.wallet.deposit(price);
+
+        let before = mob.encumbrance();
+        let mut output = vec![Update::info(
+            mob_id,
+            &format!(
+                "You buy {} from {} for {}.",
+                item.label(),
+                keeper.name(),
+                wallet::describe(price)
+            ),
+        )];
+        output.append(&mut space.announce(
+            &[mob_id],
+            &format!(
+                "{} buys {} from {}.",
+                mob.name(),
+                item.label(),
+                keeper.name()
+            ),
+        ));
+
+        mob.inventory.add(item);
+        output.extend(burden(&mob, before));
+        output.push(Update::inventory(mob_id, &mob.inventory));
+        output.push(Update::wallet(mob_id, &mob.wallet));
+
+        self.mobs.insert(mob);
+        self.mobs.insert(keeper);
+
+        Ok(output)
+    }
+
+    async fn sell(&self, mob_id: &Identifier, item_name: Option<&String>) -> CommandOutput {
+        let item_name = item_name.ok_or_else(|| TCError::user("Sell what?"))?;
+
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+        let mut keeper = self.shopkeeper(&space, mob_id)?;
+        let shop = keeper.shop.clone().unwrap_or_default();
+
+        let item = mob
+            .inventory
+            .remove(item_name)
+            .ok_or_else(|| TCError::user("You don't have that."))?;
+
+        if !item.contents.is_empty() {
+            return Err(TCError::User(format!("Empty {} first.", item.label())));
+        }
+        check_unbound(std::slice::from_ref(&item))?;
+
+        let offer = match shop.offer(&item.prototype, &mob) {
+            Some(offer) if offer > 0 => offer,
+            _ => {
+                return Err(TCError::User(format!(
+                    "{} isn't interested in that.",
+                    keeper.name()
+                )))
+            }
+        };
+
+        keeper.wallet.withdraw(offer).map_err(|_| {
+            TCError::User(format!("{} can't afford that right now.", keeper.name()))
+        })?;
+        mob.wallet.deposit(offer);
+
+        let before = mob.encumbrance();
+        let mut output = vec![Update::info(
+            mob_id,
+            &format!(
+                "You sell {} to {} for {}.",
+                item.label(),
+                keeper.name(),
+                wallet::describe(offer)
+            ),
+        )];
+        output.append(&mut space.announce(
+            &[mob_id],
+            &format!(
+                "{} sells {} to {}.",
+                mob.name(),
+                item.label(),
+                keeper.name()
+            ),
+        ));
+
+        keeper.inventory.add(item);
+        output.extend(burden(&mob, before));
+        output.push(Update::inventory(mob_id, &mob.inventory));
+        output.push(Update::wallet(mob_id, &mob.wallet));
+
+        self.mobs.insert(mob);
+        self.mobs.insert(keeper);
+
+        Ok(output)
+    }
+
+    /// What a shopkeeper would pay for something the hero is carrying
+    async fn value(&self, mob_id: &Identifier, item_name: Option<&String>) -> CommandOutput {
+        let item_name = item_name.ok_or_else(|| TCError::user("Value what?"))?;
+
+        let mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+        let keeper = self.shopkeeper(&space, mob_id)?;
+        let shop = keeper.shop.clone().unwrap_or_default();
+
+        let item = mob
+            .inventory
+            .items()
+            .iter()
+            .find(|i| i.matches(item_name))
+            .ok_or_else(|| TCError::user("You don't have that."))?;
+
+        let message = match shop.offer(&item.prototype, &mob) {
+            Some(offer) if offer > 0 => format!(
+                "{} would give you {} for {}.",
+                keeper.name(),
+                wallet::describe(offer),
+                item.label()
+            ),
+            _ => format!("{} isn't interested in that.", keeper.name()),
+        };
+
+        Ok(vec![Update::info(mob_id, &message)])
+    }
+
+    /// BARTER WITH, ACCEPT, DECLINE, ADD, REMOVE, CONFIRM, CANCEL or SHOW. Secure trades
+    /// get their own verb, so TRADE is always the trade channel.
+    async fn trade(&mut self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let subcommand = args.first().map(|a| a.to_uppercase()).unwrap_or_default();
+
+        match subcommand.as_ref() {
+            "" | "SHOW" => self.trade_status(mob_id),
+            "WITH" => {
+                let name = args
+                    .get(1)
+                    .ok_or_else(|| TCError::user("Trade with who?"))?;
+
+                let mob = self.living(mob_id)?;
+                let target = self.find_hero(name)?;
+                if target.identifier() == mob_id {
+                    return Err(TCError::user("You can't trade with yourself."));
+                }
+
+                if target.space_id != mob.space_id {
+                    return Err(TCError::user("You need to be in the same place to trade."));
+                }
+
+                self.trades.propose(mob_id, target.identifier())?;
+
+                Ok(vec![
+                    Update::info(
+                        target.identifier(),
+                        &format!(
+                            "{} wants to trade with you. (BARTER ACCEPT or BARTER DECLINE)",
+                            mob.name()
+                        ),
+                    ),
+                    Update::info(mob_id, &format!("You ask {} to trade.", target.name())),
+                ])
+            }
+            "ACCEPT" => {
+                let mob = self.living(mob_id)?;
+                let partner_id = self.trades.accept(mob_id)?;
+                let partner = self.mobs.get(&partner_id)?;
+
+                let help = "(BARTER ADD, BARTER REMOVE, BARTER CONFIRM or BARTER CANCEL)";
+                Ok(vec![
+                    Update::info(
+                        mob_id,
+                        &format!("You're trading with {}. {}", partner.name(), help),
+                    ),
+                    Update::info(
+                        &partner_id,
+                        &format!("You're trading with {}. {}", mob.name(), help),
+                    ),
+                ])
+            }
+            "DECLINE" => {
+                let mob = self.mobs.get(mob_id)?;
+                let proposer = self.trades.decline(mob_id)?;
+
+                Ok(vec![
+                    Update::info(&proposer, &format!("{} doesn't want to trade.", mob.name())),
+                    Update::info(mob_id, "You turn down the trade."),
+                ])
+            }
+            "ADD" => self.add_to_trade(mob_id, &args[1..]),
+            "REMOVE" => self.remove_from_trade(mob_id, args.get(1)),
+            "CONFIRM" => self.confirm_trade(mob_id).await,
+            "CANCEL" => {
+                let mob = self.mobs.get(mob_id)?;
+                let trade = self
+                    .trades
+                    .cancel(mob_id)
+                    .ok_or_else(|| TCError::user("You aren't trading with anyone."))?;
+
+                Ok(trade
+                    .offers
+                    .iter()
+                    .map(|o| {
+                        Update::info(
+                            &o.identifier,
+                            &format!("{} calls off the trade.", mob.name()),
+                        )
+                    })
+                    .collect())
+            }
+            _ => Err(TCError::user("Trade how?")),
+        }
+    }
+
+    /// Shows both sides of the trade to both heroes
+    fn trade_status(&self, mob_id: &Identifier) -> CommandOutput {
+        let trade = self
+            .trades
+            .get(mob_id)
+            .ok_or_else(|| TCError::user("You aren't trading with anyone."))?;
+
+        let mut output = vec![];
+        for offer in trade.offers.iter() {
+            let counter = trade
+                .counter_offer(&offer.identifier)
+                .ok_or_else(|| TCError::user("You aren't trading with anyone."))?;
+            let partner = self.mobs.get(&counter.identifier)?;
+
+            let confirmed = |o: &Offer| if o.confirmed { " (confirmed)" } else { "" };
+            let text = format!(
+                "You offer {}{}.\n{} offers {}{}.",
+                offer.describe(),
+                confirmed(offer),
+                partner.name(),
+                counter.describe(),
+                confirmed(counter)
+            );
+
+            output.push(Update::info(&offer.identifier, &text));
+        }
+
+        Ok(output)
+    }
+
+    /// Puts money (eg: "30", or "2 gold") or items (eg: "3 rocks") on the table
+    fn add_to_trade(&mut self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let mob = self.living(mob_id)?;
+        let offer = self
+            .trades
+            .get(mob_id)
+            .and_then(|t| t.offer(mob_id))
+            .cloned()
+            .ok_or_else(|| TCError::user("You aren't trading with anyone."))?;
+
+        match Denomination::parse(args) {
+            Some((copper, [])) => {
+                // anything too big to add up is more than anyone has
+                if offer.copper.saturating_add(copper) > mob.wallet.total() {
+                    return Err(TCError::user("You don't have that much money."));
+                }
+
+                if let Some(trade) = self.trades.get_mut(mob_id) {
+                    trade.amend(mob_id, |o| o.copper += copper);
+                }
+            }
+            _ => {
+                let (quantity, rest) = Quantity::parse(args);
+                let item_name = rest.first().ok_or_else(|| TCError::user("Add what?"))?;
+
+                // set aside what's already on offer, so nothing is offered twice
+                let mut inventory = mob.inventory.clone();
+                for item in &offer.items {
+                    inventory.take(item.name(), Quantity::Count(item.quantity));
+                }
+
+                let items = inventory
+                    .take(item_name, quantity)
+                    .ok_or_else(|| TCError::user("You don't have that."))?;
+                check_unbound(&items)?;
+
+                if let Some(trade) = self.trades.get_mut(mob_id) {
+                    trade.amend(mob_id, |o| items.into_iter().for_each(|i| o.add(i)));
+                }
+            }
+        }
+
+        self.trade_status(mob_id)
+    }
+
+    fn remove_from_trade(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let name = arg.ok_or_else(|| TCError::user("Remove what?"))?;
+        let money = name.eq_ignore_ascii_case("money") || Denomination::from_name(name).is_some();
+
+        let trade = self
+            .trades
+            .get_mut(mob_id)
+            .ok_or_else(|| TCError::user("You aren't trading with anyone."))?;
+
+        let on_offer = match trade.offer(mob_id) {
+            Some(o) if money => o.copper > 0,
+            Some(o) => o.items.iter().any(|i| i.matches(name)),
+            None => false,
+        };
+
+        if !on_offer {
+            return Err(TCError::user("That isn't on offer."));
+        }
+
+        trade.amend(mob_id, |o| {
+            if money {
+                o.copper = 0;
+            } else {
+                o.remove(name);
+            }
+        });
+
+        self.trade_status(mob_id)
+    }
+
+    async fn confirm_trade(&mut self, mob_id: &Identifier) -> CommandOutput {
+        let trade = self
+            .trades
+            .get_mut(mob_id)
+            .ok_or_else(|| TCError::user("You aren't trading with anyone."))?;
+
+        if !trade.confirm(mob_id) {
+            return self.trade_status(mob_id);
+        }
+
+        let trade = trade.clone();
+        match self.settle(&trade).await {
+            Ok(output) => {
+                self.trades.cancel(mob_id);
+                Ok(output)
+            }
+            Err(e) => {
+                if let Some(trade) = self.trades.get_mut(mob_id) {
+                    trade.unconfirm();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Swaps both offers in one go: if either side can't go through, neither does
+    async fn settle(&self, trade: &Trade) -> CommandOutput {
+        let [first, second] = &trade.offers;
+        let mut a = self.living(&first.identifier)?;
+        let mut b = self.living(&second.identifier)?;
+
+        if a.space_id != b.space_id {
+            return Err(TCError::user("You need to be in the same place to trade."));
+        }
+
+        let before = (a.encumbrance(), b.encumbrance());
+        let from_a = hand_over(&mut a, first)?;
+        let from_b = hand_over(&mut b, second)?;
+
+        for (mob, items) in [(&a, &from_b), (&b, &from_a)].iter() {
+            mob.can_carry(items)
+                .map_err(|_| TCError::User(format!("{} can't carry all that.", mob.name())))?;
+        }
+
+        for item in from_b {
+            a.inventory.add(item);
+        }
+        for item in from_a {
+            b.inventory.add(item);
+        }
+        a.wallet.deposit(second.copper);
+        b.wallet.deposit(first.copper);
+
+        // both heroes are written together, so nothing can be lost or copied. If that
+        // fails, neither changes here either.
+        let db = crate::services::db::Dynamo::new();
+        db.mobs
+            .put_all(&[&a, &b])
+            .await
+            .map_err(|e| TCError::System(format!("db.mobs.put_all ERROR: {}", e)))?;
+
+        let mut output = vec![];
+        for (mob, partner, offer, counter, before) in [
+            (&a, &b, first, second, before.0),
+            (&b, &a, second, first, before.1),
+        ]
+        .iter()
+        {
+            output.push(Update::info(
+                mob.identifier(),
+                &format!(
+                    "You trade {} to {} for {}.",
+                    offer.describe(),
+                    partner.name(),
+                    counter.describe()
+                ),
+            ));
+            output.extend(burden(mob, *before));
+            output.push(Update::inventory(mob.identifier(), &mob.inventory));
+            output.push(Update::wallet(mob.identifier(), &mob.wallet));
+        }
+
+        self.mobs.insert(a);
+        self.mobs.insert(b);
+
+        Ok(output)
+    }
+
+    /// Starts crafting a recipe, using up its inputs. It's finished (or botched)
+    /// when the hero is no longer busy, see `Mob::tick`.
+    async fn craft(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        if args.is_empty() {
+            return self.list_recipes(mob_id);
+        }
+
+        let name = args.join(" ").to_lowercase();
+        let recipe = self
+            .recipes
+            .get(&name)
+            .ok_or_else(|| TCError::user("You don't know how to make that."))?;
+
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+
+        if self.in_combat(&mob) {
+            return Err(TCError::user("You can't craft in the middle of a fight!"));
+        }
+
+        if mob.is_busy() {
+            return Err(TCError::user("You're busy."));
+        }
+
+        if !recipe.is_skilled(&mob) {
+            return Err(TCError::User(format!(
+                "You aren't skilled enough to make a {}.",
+                recipe.name
+            )));
+        }
+
+        if let Some(prototype) = recipe.missing(&mob) {
+            let count = recipe
+                .inputs
+                .iter()
+                .find(|(p, _)| p == prototype)
+                .map_or(1, |(_, count)| *count);
+
+            return Err(TCError::User(format!(
+                "You need {} to make a {}.",
+                self.describe_need(prototype, count),
+                recipe.name
+            )));
+        }
+
+        // the inputs are only used up once the work is done
+        mob.busy(Doing::Crafting(name.clone()), recipe.time);
+
+        let mut output = vec![Update::info(
+            mob_id,
+            &format!("You set to work on a {}.", recipe.name),
+        )];
+        output.append(&mut space.announce(
+            &[mob_id],
+            &format!("{} starts making a {}.", mob.name(), recipe.name),
+        ));
+
+        self.mobs.insert(mob);
+
+        Ok(output)
+    }
+
+    fn list_recipes(&self, mob_id: &Identifier) -> CommandOutput {
+        let mut recipes: Vec<&Recipe> = self.recipes.values().collect();
+        recipes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if recipes.is_empty() {
+            return Ok(vec![Update::info(
+                mob_id,
+                "You don't know how to make anything.",
+            )]);
+        }
+
+        let mut lines = vec!["You know how to make:".to_owned()];
+        let mut clicks = HashMap::new();
+
+        for recipe in recipes {
+            let inputs: Vec<String> = recipe
+                .inputs
+                .iter()
+                .map(|(prototype, count)| self.describe_need(prototype, *count))
+                .collect();
+            let tools: Vec<String> = recipe
+                .tools
+                .iter()
+                .map(|prototype| self.describe_need(prototype, 1))
+                .collect();
+
+            let mut needs = inputs.join(", ");
+            if !tools.is_empty() {
+                needs.push_str(&format!(", using {}", tools.join(", ")));
+            }
+
+            lines.push(format!(
+                "[[{}]]: {} ({})",
+                recipe.name, recipe.description, needs
+            ));
+            clicks.insert(recipe.name.clone(), format!("craft {}", recipe.name));
+        }
+
+        Ok(vec![Update::item(
+            mob_id,
+            Markup {
+                text: lines.join("\n"),
+                clicks,
+            },
+        )])
+    }
+
+    /// Describes some number of an Item prototype (eg: "a stick", or "2 rocks")
+    fn describe_need(&self, prototype: &str, count: usize) -> String {
+        match self.item_prototypes.create(prototype, &mut Dice::new()) {
+            Some(mut item) if count > 1 => {
+                item.quantity = count;
+                item.label()
+            }
+            Some(item) => format!("a {}", item.name()),
+            None => prototype.to_lowercase(),
+        }
+    }
+
+    /// Gives one of the hero's items a name of its own
+    async fn engrave(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let (item_name, words) = args
+            .split_first()
+            .ok_or_else(|| TCError::user("Engrave what?"))?;
+
+        if words.is_empty() {
+            return Err(TCError::user("What do you want to engrave on it?"));
+        }
+
+        let title = words.join(" ");
+        if title.len() > MAX_TITLE {
+            return Err(TCError::user("That won't fit."));
+        }
+
+        if !is_engravable(&title) {
+            return Err(TCError::user(
+                "You can only engrave letters, numbers and simple punctuation.",
+            ));
+        }
+
+        let mut mob = self.living(mob_id)?;
+
+        // engrave what's equipped first, and only one item from a stack
+        let mut output = vec![Update::info(
+            mob_id,
+            &format!("You engrave \"{}\" on the {}.", title, item_name),
+        )];
+
+        if let Some(mut item) = mob.equipment.remove(item_name) {
+            item.title = Some(title);
+            mob.equipment.equip(item);
+            output.push(Update::equipment(mob_id, &mob.equipment));
+        } else {
+            let mut item = mob
+                .inventory
+                .remove(item_name)
+                .ok_or_else(|| TCError::user("You don't have that."))?;
+            item.title = Some(title);
+            mob.inventory.add(item);
+            output.push(Update::inventory(mob_id, &mob.inventory));
+        }
+
+        self.mobs.insert(mob);
+
+        Ok(output)
+    }
+
+    /// A summary of how the hero is getting on
+    async fn score(&self, mob_id: &Identifier) -> CommandOutput {
+        let mob = self.mobs.get(mob_id)?;
+
+        let load = match mob.encumbrance() {
+            Encumbrance::Unburdened => "unburdened",
+            Encumbrance::Burdened => "burdened",
+            Encumbrance::Stressed => "stressed",
+        };
+
+        let text = format!(
+            "{}\n\nHealth: {}/{}\nExperience: {}\nMoney: {}\nLoad: {}/{} ({})\nQuests: {}",
+            mob.name(),
+            mob.hp,
+            mob.max_health(),
+            mob.experience,
+            mob.wallet.describe(),
+            mob.load(),
+            mob.carry_limit(),
+            load,
+            mob.quests.len(),
+        );
+
+        Ok(vec![
+            Update::character(
+                mob_id,
+                Markup {
+                    text,
+                    clicks: HashMap::new(),
+                },
+            ),
+            Update::wallet(mob_id, &mob.wallet),
+        ])
+    }
+
+    /// Brings a ghost back to life in the recall space for where they died
+    async fn respawn(&self, mob_id: &Identifier) -> CommandOutput {
+        let mut mob = self.mobs.get(mob_id)?;
+        if !mob.ghost {
+            return Err(TCError::user("You're not dead yet!"));
+        }
+
+        let mut current_space = self.spaces.get(&mob.space_id)?;
+        let recall_id = current_space.recall();
+
+        let mut recall_space = match self.spaces.get(&recall_id) {
+            Ok(s) => s,
+            Err(e) => {
+                error!(
+                    "Couldn't find recall space {}, using ORIGIN. {:?}",
+                    recall_id, e
+                );
+                self.spaces.get(&Identifier::origin())?
+            }
+        };
+
+        mob.respawn();
+
+        let mut output = vec![Update::info(mob_id, "You return to life!")];
+
+        output.append(&mut current_space.announce(
+            &[mob_id],
+            &format!("The ghost of {} fades away.", mob.name()),
+        ));
+
+        output.append(&mut recall_space.announce(
+            &[mob_id],
+            &format!("{} appears in a flash of light.", mob.name()),
+        ));
+        output.push(Update::space(mob_id, recall_space.describe(self)));
+        output.push(Update::health(mob_id, mob.health()));
+
+        let moved = recall_space.identifier() != current_space.identifier();
+        if moved {
+            current_space.population.remove(mob_id);
+            recall_space.population.add(mob_id);
+            mob.space_id = recall_space.identifier().clone();
+        }
+
+        self.mobs.insert(mob);
+
+        if moved {
+            output.append(&mut current_space.population_update(self));
+            self.spaces.insert(current_space);
+        }
+        output.append(&mut recall_space.population_update(self));
+        self.spaces.insert(recall_space);
+
+        Ok(output)
+    }
+
+    async fn inventory(&self, mob_id: &Identifier) -> CommandOutput {
+        let mob = self.mobs.get(mob_id)?;
+        let inventory = &mob.inventory;
+        let update = Update::inventory(mob_id, inventory);
+
+        Ok(vec![update])
+    }
+
+    async fn fight(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let target_name = arg.ok_or_else(|| TCError::user("Fight who?"))?;
+
+        let mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+
+        for local_mob in &space.population.mobs {
+            let mut target_mob = self.mobs.get(&local_mob)?;
+            // add the enemy if the name matches and it isn't yourself!
+            if target_mob.name() == target_name && target_mob.identifier() != mob.identifier() {
+                if target_mob.ghost {
+                    return Err(TCError::user("They're already dead."));
+                }
+
+                if self
+                    .parties
+                    .are_allies(mob.identifier(), target_mob.identifier())
+                {
+                    return Err(TCError::user("You can't attack your own group!"));
+                }
+
+                let mut player = mob;
+
+                // both mobs (and the target's group) become enemies!
+                self.provoke(&mut player, &mut target_mob);
+
+                let mut output = vec![];
+
+                output.push(Update::combat(
+                    player.identifier(),
+                    format!("You attack {}!", target_name),
+                ));
+
+                output.push(Update::combat(
+                    target_mob.identifier(),
+                    format!("{} attacks you!", player.name()),
+                ));
+
+                let player_name = player.name().to_owned();
+                self.mobs.insert(player);
+                self.mobs.insert(target_mob.clone());
+
+                output.append(&mut self.script(&target_mob, Event::Attacked(player_name)));
+
+                return Ok(output);
+            }
+        }
+
+        Err(TCError::user("You don't see them here."))
+    }
+
+    async fn wimpy(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let mut mob = self.mobs.get(mob_id)?;
+
+        if let Some(raw) = arg {
+            let pct: usize = raw.trim_end_matches('%').parse().map_err(|_| {
+                TCError::user("Set wimpy to a percentage of your health, or 0 to turn it off.")
+            })?;
+
+            if pct > MAX_WIMPY {
+                return Err(TCError::User(format!(
+                    "Wimpy can't be higher than {}%.",
+                    MAX_WIMPY
+                )));
+            }
+
+            mob.wimpy = pct;
+        }
+
+        let message = match mob.wimpy {
+            0 => "Wimpy is off. You'll fight to the death!".to_owned(),
+            pct => format!("You'll try to flee below {}% health.", pct),
+        };
+
+        self.mobs.insert(mob);
+
+        Ok(vec![Update::info(mob_id, &message)])
+    }
+
+    /// Offers to stop fighting. Monsters decide on the spot, but heroes both have
+    /// to yield to each other before the fight ends.
+    async fn surrender(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let target_name = arg.ok_or_else(|| TCError::user("Yield to who?"))?;
+
+        let mut mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+
+        let mut target = space
+            .population
+            .identifiers()
+            .iter()
+            .flat_map(|id| self.mobs.get(id))
+            .find(|m| m.identifier() != mob_id && m.name().eq_ignore_ascii_case(target_name))
+            .ok_or_else(|| TCError::user("You don't see them here."))?;
+
+        if !mob.enemies.contains(target.identifier()) {
+            return Err(TCError::user("You aren't fighting them."));
+        }
+
+        let mut output = vec![];
+
+        let agreed = if target.is_hero() {
+            if target.yielding_to.as_ref() == Some(mob_id) {
+                true
+            } else {
+                mob.yielding_to = Some(target.identifier().clone());
+                output.push(Update::combat(
+                    mob_id,
+                    format!("You offer to end the fight with {}.", target.name()),
+                ));
+                output.push(Update::combat(
+                    target.identifier(),
+                    format!(
+                        "{} yields! YIELD {} to agree and end the fight.",
+                        mob.name(),
+                        mob.name()
+                    ),
+                ));
+                false
+            }
+        } else if combat::surrender_roll(&mob, &target, &mut Dice::new()) {
+            true
+        } else {
+            output.push(Update::combat(
+                mob_id,
+                format!("{} refuses to accept your surrender!", target.name()),
+            ));
+            false
+        };
+
+        if agreed {
+            mob.remove_enemy(target.identifier());
+            target.remove_enemy(mob_id);
+            mob.yielding_to = None;
+            target.yielding_to = None;
+
+            output.push(Update::combat(
+                mob_id,
+                format!("You and {} stop fighting.", target.name()),
+            ));
+            output.push(Update::combat(
+                target.identifier(),
+                format!("You and {} stop fighting.", mob.name()),
+            ));
+            output.append(&mut space.announce(
+                &[mob_id, target.identifier()],
+                &format!("{} yields to {}.", mob.name(), target.name()),
+            ));
+        }
+
+        self.mobs.insert(mob);
+        self.mobs.insert(target);
+
+        Ok(output)
+    }
+
+    /// Lists the heroes in the world, with how long they've been idle
+    async fn who(&self, mob_id: &Identifier) -> CommandOutput {
+        let mut heroes = self.mobs.select(|m| self.online.contains(m.identifier()));
+        heroes.sort_by(|a, b| a.name().cmp(b.name()));
+
+        let listing = heroes
+            .iter()
+            .map(|h| {
+                let idle = h.active.map(|a| a.elapsed()).unwrap_or_default();
+                let mut line = format!("{} (idle {})", h.name(), idle_time(idle));
+                if let Some(reason) = &h.away {
+                    line += &format!(" [away: {}]", reason);
+                }
+                line
+            })
+            .collect();
+
+        Ok(vec![Update::who(mob_id, listing)])
+    }
+
+    /// AFK on its own toggles the away flag; AFK with a reason always sets it
+    async fn afk(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let mut mob = self.mobs.get(mob_id)?;
+        let reason = Some(args.join(" ")).filter(|r| !r.is_empty());
+
+        let message = if mob.away.is_some() && reason.is_none() {
+            mob.away = None;
+            "You are no longer away.".to_owned()
+        } else {
+            let reason = reason.unwrap_or_else(|| "away".to_owned());
+            let message = format!("You are away: {}", reason);
+            mob.away = Some(reason);
+            message
+        };
+
+        self.mobs.insert(mob);
+
+        Ok(vec![Update::info(mob_id, &message)])
+    }
+
+    /// Turns login/logout notices on or off, or toggles them. Saved with the hero.
+    async fn notify(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let mut mob = self.mobs.get(mob_id)?;
+
+        mob.notify = match arg.map(|a| a.to_lowercase()).as_deref() {
+            Some("on") => true,
+            Some("off") => false,
+            None => !mob.notify,
+            Some(_) => return Err(TCError::user("NOTIFY ON, or NOTIFY OFF?")),
+        };
+
+        let message = if mob.notify {
+            "You'll be notified when heroes arrive and leave."
+        } else {
+            "You won't be notified when heroes arrive and leave."
+        };
+
+        self.mobs.insert(mob);
+
+        Ok(vec![Update::info(mob_id, message)])
+    }
+
+    async fn time(&self, mob_id: &Identifier) -> CommandOutput {
+        Ok(vec![Update::time(mob_id, &self.clock.into())])
+    }
+
+    async fn save(&self, mob_id: &Identifier) -> CommandOutput {
+        let mob = self.mobs.get(mob_id)?;
+        let db = crate::services::db::Dynamo::new();
+
+        match db.mobs.put(&mob).await {
+            Ok(_) => Ok(vec![Update::info(mob_id, "Saved!")]),
+            Err(e) => {
+                warn!("db.mobs.put ERROR: {}", e);
+                Err(TCError::user("Something went wrong ..."))
+            }
+        }
+    }
+
+    async fn quit(&mut self, mob_id: &Identifier) -> CommandOutput {
+        // fetch the affected entities
+        let mob = self.mobs.get(mob_id)?;
+        let mut space = self.spaces.get(&mob.space_id)?;
+
+        // remove the mob from the population of the space
+        space.population.remove(mob.identifier());
+        self.online.remove(mob_id);
+
+        // and from any chat channels and groups
+        self.channels.leave_all(mob.identifier());
+        let mut output = self.leave_group(mob_id)?;
+        output.append(&mut self.call_off_trade(&mob));
+
+        output.append(&mut self.notice(&mob, &format!("{} has left.", mob.name())));
+
+        // axe the mob from the cache
+        self.mobs.remove(mob_id);
+
+        // let everyone else know, and save the space
+        output.append(&mut space.announce(&[], &format!("{} fades away.", mob.name())));
+        output.append(&mut space.population_update(&self));
+        self.spaces.insert(space);
+
+        // say buh-bye!
+        output.push(Update::info(mob_id, "See you later!"));
+        Ok(output)
+
+        // TODO: Figure out how to close the connection!!
+    }
+
+    async fn list_channels(&self, mob_id: &Identifier) -> CommandOutput {
+        let listing: Vec<String> = self
+            .channels
+            .names()
+            .into_iter()
+            .map(|name| {
+                if self.channels.is_subscribed(&name, mob_id) {
+                    format!("{} (joined)", name)
+                } else {
+                    name
+                }
+            })
+            .collect();
+
+        Ok(vec![Update::info(
+            mob_id,
+            &format!("Channels: {}", listing.join(", ")),
+        )])
+    }
+
+    async fn join(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let channel = arg
+            .ok_or_else(|| TCError::user("Join which channel?"))?
+            .to_lowercase();
+
+        self.channels.join(&channel, mob_id)?;
+
+        // catch them up on what they missed
+        let mut output = vec![Update::info(mob_id, &format!("You joined {}.", channel))];
+        let mob = self.mobs.get(mob_id)?;
+        for line in self.channels.history(&channel, &mob.muted)? {
+            output.push(Update::chat(mob_id, &line));
+        }
+
+        Ok(output)
+    }
+
+    async fn leave(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let channel = arg
+            .ok_or_else(|| TCError::user("Leave which channel?"))?
+            .to_lowercase();
+
+        self.channels.leave(&channel, mob_id)?;
+
+        Ok(vec![Update::info(
+            mob_id,
+            &format!("You left {}.", channel),
+        )])
+    }
+
+    async fn history(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let channel = arg
+            .ok_or_else(|| TCError::user("History of which channel?"))?
+            .to_lowercase();
+
+        let mob = self.mobs.get(mob_id)?;
+        let history = self.channels.history(&channel, &mob.muted)?;
+        if history.is_empty() {
+            return Ok(vec![Update::info(mob_id, "It's been quiet.")]);
+        }
+
+        Ok(history
+            .iter()
+            .map(|line| Update::chat(mob_id, line))
+            .collect())
+    }
+
+    async fn mute(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let name = arg.ok_or_else(|| TCError::user("Mute who?"))?;
+        let target = self.find_hero(name)?;
+
+        if target.identifier() == mob_id {
+            return Err(TCError::user("You can't mute yourself."));
+        }
+
+        let mut mob = self.mobs.get(mob_id)?;
+        mob.mute(target.identifier());
+        self.mobs.insert(mob);
+
+        Ok(vec![Update::info(
+            mob_id,
+            &format!("You muted {}.", target.name()),
+        )])
+    }
+
+    async fn unmute(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let name = arg.ok_or_else(|| TCError::user("Unmute who?"))?;
+        let target = self.find_hero(name)?;
+
+        let mut mob = self.mobs.get(mob_id)?;
+        mob.unmute(target.identifier());
+        self.mobs.insert(mob);
+
+        Ok(vec![Update::info(
+            mob_id,
+            &format!("You unmuted {}.", target.name()),
+        )])
+    }
+
+    async fn chat(&mut self, mob_id: &Identifier, channel: &str, args: &[String]) -> CommandOutput {
+        if args.is_empty() {
+            return Err(TCError::user("Say what?"));
+        }
+
+        let mob = self.mobs.get(mob_id)?;
+        let mobs = &self.mobs;
+        self.channels.send(
+            channel,
+            mob_id,
+            mob.name(),
+            &args.join(" "),
+            |listener| match mobs.get(listener) {
+                Ok(l) => !l.is_muting(mob_id),
+                Err(_) => true,
+            },
+        )
+    }
+
+    async fn say(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        if args.is_empty() {
+            return Err(TCError::user("Say what?"));
+        }
+
+        let message = args.join(" ");
+        let mob = self.living(mob_id)?;
+        let space = self.spaces.get(&mob.space_id)?;
+
+        let mut output = vec![Update::info(mob_id, &format!("You say, \"{}\"", message))];
+        output.append(
+            &mut space.announce(&[mob_id], &format!("{} says, \"{}\"", mob.name(), message)),
+        );
+
+        for local in space
+            .population
+            .identifiers()
+            .iter()
+            .filter(|id| *id != mob_id)
+            .flat_map(|id| self.mobs.get(id))
+        {
+            let event = Event::Say(mob.name().to_owned(), message.clone());
+            output.append(&mut self.script(&local, event));
+        }
+
+        Ok(output)
+    }
+
+    /// Starts a conversation with an NPC (`TALK <npc>`), or answers them (`TALK <npc> <choice>`)
+    async fn talk(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        // names can run to several words, so a choice is only ever the last word
+        let (npc_name, choice) = match args.split_last() {
+            None => return Err(TCError::user("Talk to who?")),
+            Some((last, rest)) if !rest.is_empty() && last.parse::<usize>().is_ok() => {
+                (rest.join(" "), Some(last))
+            }
+            Some(_) => (args.join(" "), None),
+        };
+
+        let mut hero = self.living(mob_id)?;
+        let mut space = self.spaces.get(&hero.space_id)?;
+
+        let npc = space
+            .population
+            .identifiers()
+            .iter()
+            .flat_map(|id| self.mobs.get(id))
+            .find(|m| m.identifier() != mob_id && m.name().eq_ignore_ascii_case(&npc_name))
+            .ok_or_else(|| TCError::user("You don't see them here."))?;
+
+        let dialogue = self
+            .dialogues
+            .get(&npc.prototype)
+            .ok_or_else(|| TCError::User(format!("{} has nothing to say.", npc.name())))?;
+
+        // pick up where we left off, but only if it was with this NPC
+        let node_id = match choice {
+            None => dialogue::START.to_owned(),
+            Some(raw) => {
+                let current = match &hero.conversation {
+                    Some((with, node)) if with == npc.identifier() => node.to_owned(),
+                    _ => return Err(TCError::user("You aren't talking to them.")),
+                };
+
+                let node = dialogue
+                    .node(&current)
+                    .ok_or_else(|| TCError::user("You aren't talking to them."))?;
+
+                let number: usize = raw
+                    .parse()
+                    .map_err(|_| TCError::user("Pick one of the numbered choices."))?;
+
+                node.available(&hero)
+                    .into_iter()
+                    .find(|(n, _)| *n == number)
+                    .map(|(_, c)| c.goto.clone())
+                    .ok_or_else(|| TCError::user("That's not one of the choices."))?
+            }
+        };
+
+        let node = dialogue.node(&node_id).ok_or_else(|| {
+            TCError::System(format!(
+                "Missing dialogue node {} for {}",
+                node_id, npc.prototype
+            ))
+        })?;
+
+        let mut output = vec![];
+        let mut dropped = false;
+
+        let dice = &mut Dice::new();
+        for consequence in &node.consequences {
+            match consequence {
+                Consequence::GiveItem(prototype) => {
+                    match self.item_prototypes.create(prototype, dice) {
+                        // gifts are bound by the same limits as anything else you pick up
+                        Some(item) if hero.can_carry(std::slice::from_ref(&item)).is_err() => {
+                            output.push(Update::info(
+                                mob_id,
+                                &format!(
+                                    "{} offers you a {}, but you can't carry it, so it's left at your feet.",
+                                    npc.name(),
+                                    item.name()
+                                ),
+                            ));
+                            space.inventory.add(item);
+                            dropped = true;
+                        }
+                        Some(item) => {
+                            output.push(Update::info(
+                                mob_id,
+                                &format!("{} gives you a {}.", npc.name(), item.name()),
+                            ));
+                            hero.inventory.add(item);
+                            output.push(Update::inventory(mob_id, &hero.inventory));
+                        }
+                        None => warn!(
+                            "Dialogue for {} gives unknown item {}",
+                            npc.prototype, prototype
+                        ),
+                    }
+                }
+                Consequence::SetFlag(flag) => hero.set_flag(flag),
+                Consequence::StartQuest(quest) => {
+                    if hero.start_quest(quest) {
+                        output.push(Update::info(mob_id, &format!("New quest: {}", quest)));
+                    }
+                }
+            }
+        }
+
+        // render the choices as clickable, numbered answers
+        let mut markup = Markup {
+            text: format!("{}: {}", npc.name(), node.text),
+            clicks: HashMap::new(),
+        };
+
+        let choices = node.available(&hero);
+        for (number, choice) in &choices {
+            markup.text += &format!("\n\n{}. [[{}]]", number, choice.label);
+            markup.clicks.insert(
+                choice.label.clone(),
+                format!("talk {} {}", npc.name(), number),
+            );
+        }
+
+        hero.conversation = if choices.is_empty() {
+            None
+        } else {
+            Some((npc.identifier().clone(), node_id))
+        };
+
+        if dropped {
+            output.append(&mut space.space_update(self));
+            self.spaces.insert(space);
+        }
+
+        output.insert(0, Update::dialogue(mob_id, markup));
+        self.mobs.insert(hero);
+
+        Ok(output)
+    }
+
+    async fn list_quests(&self, mob_id: &Identifier) -> CommandOutput {
+        let mob = self.mobs.get(mob_id)?;
+
+        let message = if mob.quests.is_empty() {
+            "You haven't taken on any quests.".to_owned()
+        } else {
+            format!("Quests: {}", mob.quests.join(", "))
+        };
+
+        Ok(vec![Update::info(mob_id, &message)])
+    }
+
+    async fn tell(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let (name, words) = args
+            .split_first()
+            .ok_or_else(|| TCError::user("Tell who?"))?;
+
+        if words.is_empty() {
+            return Err(TCError::user("Tell them what?"));
+        }
+
+        let to = self.hero_identifier(name).await?;
+        self.send_tell(mob_id, &to, &words.join(" ")).await
+    }
+
+    async fn reply(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        if args.is_empty() {
+            return Err(TCError::user("Reply with what?"));
+        }
+
+        let mob = self.mobs.get(mob_id)?;
+        let to = mob
+            .reply_to
+            .ok_or_else(|| TCError::user("Nobody has told you anything yet."))?;
+
+        self.send_tell(mob_id, &to, &args.join(" ")).await
+    }
+
+    /// Delivers a tell to a connected hero, or leaves it in their mailbox if they're offline
+    async fn send_tell(
+        &self,
+        mob_id: &Identifier,
+        to: &Identifier,
+        message: &str,
+    ) -> CommandOutput {
+        if to == mob_id {
+            return Err(TCError::user("Talking to yourself again?"));
+        }
+
+        let mob = self.mobs.get(mob_id)?;
+
+        // connected heroes hear it right away
+        if let Ok(mut target) = self.mobs.get(to) {
+            if target.is_blocking(mob_id) {
+                return Err(TCError::User(format!(
+                    "{} isn't listening to you.",
+                    target.name()
+                )));
+            }
+
+            target.reply_to = Some(mob_id.clone());
+
+            let output = vec![
+                Update::tell(to, &format!("{} tells you: {}", mob.name(), message)),
+                Update::tell(mob_id, &format!("You tell {}: {}", target.name(), message)),
+            ];
+
+            self.mobs.insert(target);
+
+            return Ok(output);
+        }
+
+        // otherwise, it waits in their mailbox
+        let db = Dynamo::new();
+        let target = db
+            .mobs
+            .get::<Mob>(&to.value)
+            .await
+            .ok_or_else(|| TCError::user("There's nobody by that name."))?;
+
+        if target.is_blocking(mob_id) {
+            return Err(TCError::User(format!(
+                "{} isn't listening to you.",
+                target.name()
+            )));
+        }
+
+        let mut mailbox = db
+            .mailboxes
+            .get::<Mailbox>(&to.value)
+            .await
+            .unwrap_or_else(|| Mailbox {
+                identifier: to.clone(),
+                letters: vec![],
+            });
+
+        if mailbox.letters.len() >= MAILBOX_LIMIT {
+            return Err(TCError::User(format!(
+                "{}'s mailbox is full.",
+                target.name()
+            )));
+        }
+
+        mailbox.letters.push(Letter {
+            from: mob_id.clone(),
+            name: mob.name().to_owned(),
+            message: message.to_owned(),
+        });
+
+        db.mailboxes.put(&mailbox).await.map_err(TCError::System)?;
+
+        Ok(vec![Update::tell(
+            mob_id,
+            &format!(
+                "{} is away, but will get your message when they return.",
+                target.name()
+            ),
+        )])
+    }
+
+    async fn block(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let name = arg.ok_or_else(|| TCError::user("Block who?"))?;
+        let target = self.hero_identifier(name).await?;
+
+        if &target == mob_id {
+            return Err(TCError::user("You can't block yourself."));
+        }
+
+        let mut mob = self.mobs.get(mob_id)?;
+        mob.block(&target);
+        self.mobs.insert(mob);
+
+        Ok(vec![Update::info(
+            mob_id,
+            &format!("You won't hear tells from {} anymore.", name),
+        )])
+    }
+
+    async fn unblock(&self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let name = arg.ok_or_else(|| TCError::user("Unblock who?"))?;
+        let target = self.hero_identifier(name).await?;
+
+        let mut mob = self.mobs.get(mob_id)?;
+        mob.unblock(&target);
+        self.mobs.insert(mob);
+
+        Ok(vec![Update::info(
+            mob_id,
+            &format!("You'll hear tells from {} again.", name),
+        )])
+    }
+
+    async fn group(&mut self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        let subcommand = args.first().map(|a| a.to_uppercase()).unwrap_or_default();
+
+        match subcommand.as_ref() {
+            "" => {
+                let names: Vec<String> = self
+                    .parties
+                    .members(mob_id)
+                    .iter()
+                    .flat_map(|id| self.mobs.get(id))
+                    .map(|m| m.name)
+                    .collect();
+
+                if names.len() < 2 {
+                    return Ok(vec![Update::info(mob_id, "You're on your own.")]);
+                }
+
+                Ok(vec![Update::info(
+                    mob_id,
+                    &format!("Your group: {}", names.join(", ")),
+                )])
+            }
+            "INVITE" => {
+                let name = args.get(1).ok_or_else(|| TCError::user("Invite who?"))?;
+
+                let mob = self.mobs.get(mob_id)?;
+                let target = self.find_hero(name)?;
+                if target.identifier() == mob_id {
+                    return Err(TCError::user("You're always in your own company."));
+                }
+
+                self.parties.invite(mob_id, target.identifier())?;
+
+                Ok(vec![
+                    Update::info(
+                        target.identifier(),
+                        &format!(
+                            "{} invites you to join their group. (GROUP ACCEPT or GROUP DECLINE)",
+                            mob.name()
+                        ),
+                    ),
+                    Update::info(mob_id, &format!("You invite {}.", target.name())),
+                ])
+            }
+            "ACCEPT" => {
+                let mob = self.mobs.get(mob_id)?;
+                let leader = self.parties.accept(mob_id)?;
+
+                Ok(self
+                    .parties
+                    .members(&leader)
+                    .iter()
+                    .map(|member| {
+                        Update::info(member, &format!("{} joined the group.", mob.name()))
+                    })
+                    .collect())
+            }
+            "DECLINE" => {
+                let mob = self.mobs.get(mob_id)?;
+                let leader = self.parties.decline(mob_id)?;
+
+                Ok(vec![
+                    Update::info(
+                        &leader,
+                        &format!("{} declined your invitation.", mob.name()),
+                    ),
+                    Update::info(mob_id, "You declined the invitation."),
+                ])
+            }
+            "LEAVE" => {
+                if self.parties.leader_of(mob_id).is_none() {
+                    return Err(TCError::user("You're not in a group."));
+                }
+
+                let mut output = self.leave_group(mob_id)?;
+                output.push(Update::info(mob_id, "You left the group."));
+                Ok(output)
+            }
+            _ => Err(TCError::user(
+                "GROUP, GROUP INVITE <name>, GROUP ACCEPT, GROUP DECLINE, or GROUP LEAVE?",
+            )),
+        }
+    }
+
+    async fn party(&self, mob_id: &Identifier, args: &[String]) -> CommandOutput {
+        if args.is_empty() {
+            return Err(TCError::user("Say what?"));
+        }
+
+        if self.parties.leader_of(mob_id).is_none() {
+            return Err(TCError::user("You're not in a group."));
+        }
+
+        let mob = self.mobs.get(mob_id)?;
+        let line = format!("[party] {}: {}", mob.name(), args.join(" "));
+
+        Ok(self
+            .parties
+            .members(mob_id)
+            .iter()
+            .map(|member| Update::chat(member, &line))
+            .collect())
+    }
+
+    async fn follow(&mut self, mob_id: &Identifier, arg: Option<&String>) -> CommandOutput {
+        let mut mob = self.mobs.get(mob_id)?;
+
+        // no name means stop following
+        let target_name = match arg {
+            Some(name) => name,
+            None => {
+                let leader_id = mob
+                    .following
+                    .take()
+                    .ok_or_else(|| TCError::user("You're not following anyone."))?;
+                self.mobs.insert(mob);
+
+                let mut output = vec![Update::info(mob_id, "You stop following.")];
+                if let Ok(leader) = self.mobs.get(&leader_id) {
+                    output.push(Update::info(
+                        leader.identifier(),
+                        &format!("{} stops following you.", self.mobs.get(mob_id)?.name()),
+                    ));
+                }
+                return Ok(output);
+            }
+        };
+
+        let space = self.spaces.get(&mob.space_id)?;
+        let leader = space
+            .population
+            .identifiers()
+            .iter()
+            .flat_map(|id| self.mobs.get(id))
+            .find(|m| m.name() == target_name)
+            .ok_or_else(|| TCError::user("You don't see them here."))?;
+
+        if leader.identifier() == mob_id {
+            return Err(TCError::user("You can't follow yourself."));
+        }
+
+        mob.following = Some(leader.identifier().clone());
+        let output = vec![
+            Update::info(mob_id, &format!("You start following {}.", leader.name())),
+            Update::info(
+                leader.identifier(),
+                &format!("{} starts following you.", mob.name()),
+            ),
+        ];
+        self.mobs.insert(mob);
+
+        Ok(output)
+    }
+}
+
+/// Lets a mob know when their load gets heavier or lighter
+fn burden(mob: &Mob, before: Encumbrance) -> Option<Update> {
+    let after = mob.encumbrance();
+    if after == before {
+        return None;
+    }
+
+    Some(Update::info(mob.identifier(), after.message()))
+}
+
+/// Takes what a hero offered out of their inventory and wallet, checking it's all still there
+fn hand_over(mob: &mut Mob, offer: &Offer) -> Result<Vec<Item>, TCError> {
+    let mut items = vec![];
+
+    for item in &offer.items {
+        let mut taken = mob
+            .inventory
+            .take(item.name(), Quantity::Count(item.quantity))
+            .ok_or_else(|| {
+                TCError::User(format!("{} no longer has {}.", mob.name(), item.label()))
+            })?;
+        items.append(&mut taken);
+    }
+
+    // what's taken by name now may not be what was offered
+    check_unbound(&items)?;
+
+    mob.wallet.withdraw(offer.copper).map_err(|_| {
+        TCError::User(format!(
+            "{} no longer has {}.",
+            mob.name(),
+            wallet::describe(offer.copper)
+        ))
+    })?;
+
+    Ok(items)
+}
+
+/// Titles are shown to everyone who looks at the item, so they're kept to plain words
+fn is_engravable(title: &str) -> bool {
+    title
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == ' ' || ".,!?'-".contains(c))
+}
+
+/// Bound items can't change hands
+fn check_unbound(items: &[Item]) -> Result<(), TCError> {
+    match items.iter().find(|i| i.bound_to.is_some()) {
+        Some(item) => Err(TCError::User(format!(
+            "You can't part with {}, it's bound.",
+            item.label()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Picks the recipient of money out of the end of a GIVE (eg: "to Bob", or just
+/// "Bob"). Without a coin word, only "to" tells an amount of money ("2 to Bob")
+/// from a number of things ("2 rocks").
+fn recipient<'a>(args: &[String], rest: &'a [String]) -> Option<&'a String> {
+    let coins = args
+        .get(1)
+        .and_then(|word| Denomination::from_name(word))
+        .is_some();
+
+    match rest {
+        [to, name] if to.eq_ignore_ascii_case("to") => Some(name),
+        [name] if coins => Some(name),
+        _ => None,
+    }
+}
+
+/// Describes a handful of items taken together (eg: "the rock", or "3 rocks")
+fn label(items: &[Item]) -> String {
+    match items {
+        [item] => item.label(),
+        _ => {
+            let total: usize = items.iter().map(|i| i.quantity).sum();
+            let plural = items.first().map(|i| i.plural()).unwrap_or_default();
+            format!("{} {}", total, plural)
+        }
+    }
+}
+
+/// Finds a container by name, checking what the mob carries before what's lying around
+fn container_mut<'a>(
+    carried: &'a mut Inventory,
+    here: &'a mut Inventory,
+    name: &str,
+) -> Result<&'a mut Item, TCError> {
+    match carried.find_mut(|i| i.name() == name) {
+        Some(container) => Ok(container),
+        None => here
+            .find_mut(|i| i.name() == name)
+            .ok_or_else(|| TCError::user("You don't see that here.")),
+    }
+}
+
+/// Describes a mob leaving through an exit (eg: "Hero12 leaves north.")
+fn departure(name: &str, direction: &str) -> String {
+    if is_compass(direction) {
+        format!("{} leaves {}.", name, direction)
+    } else {
+        format!("{} goes {}.", name, direction)
+    }
+}
+
+/// Describes a mob arriving, given the exit that leads back to where they came from
+fn arrival(name: &str, from: Option<&str>) -> String {
+    match from {
+        Some("in") => format!("{} arrives from inside.", name),
+        Some("out") => format!("{} arrives from outside.", name),
+        Some("up") => format!("{} arrives from above.", name),
+        Some("down") => format!("{} arrives from below.", name),
+        Some(direction) if is_compass(direction) => {
+            format!("{} arrives from the {}.", name, direction)
+        }
+        _ => format!("{} arrives.", name),
+    }
+}
+
+/// Formats an idle duration in its largest whole unit (eg: `3m`)
+fn idle_time(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m", seconds / 60),
+        _ => format!("{}h", seconds / 3600),
+    }
+}
+
+fn is_compass(direction: &str) -> bool {
+    [
+        "north",
+        "south",
+        "east",
+        "west",
+        "northeast",
+        "northwest",
+        "southeast",
+        "southwest",
+    ]
+    .contains(&direction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_departure_and_arrival() {
+        assert_eq!(departure("Hero1", "north"), "Hero1 leaves north.");
+        assert_eq!(departure("Hero1", "in"), "Hero1 goes in.");
+        assert_eq!(
+            arrival("Hero1", Some("south")),
+            "Hero1 arrives from the south."
+        );
+        assert_eq!(arrival("Hero1", Some("out")), "Hero1 arrives from outside.");
+        assert_eq!(arrival("Hero1", Some("portal")), "Hero1 arrives.");
+        assert_eq!(arrival("Hero1", None), "Hero1 arrives.");
+    }
+
+    #[test]
+    fn test_is_engravable() {
+        assert!(is_engravable("Old Faithful"));
+        assert!(is_engravable("Bob's 2nd spear, v-2!"));
+        assert!(!is_engravable("<img src=x onerror=alert(1)>"));
+        assert!(!is_engravable("[[click]]"));
+    }
+
+    /// Puts a hero with the given name into the world
+    fn hero(world: &World, name: &str) -> Identifier {
+        let mut mob = Mob::new();
+        mob.prototype = "HERO".to_owned();
+        mob.name = name.to_owned();
+        let id = mob.identifier().clone();
+        world.mobs.insert(mob);
+        id
+    }
+
+    #[test]
+    fn test_who_lists_connected_heroes() {
+        let mut world = World::new();
+        let stays = hero(&world, "Stays");
+        let leaves = hero(&world, "Leaves");
+        hero(&world, "Never");
+
+        world.connect(&stays);
+        world.connect(&leaves);
+        world.disconnect(&leaves);
+
+        let updates = tokio_test::block_on(world.who(&stays)).unwrap();
+        match &updates[0].message {
+            update::Wrapper::Who(listing) => {
+                assert_eq!(listing.len(), 1);
+                assert!(listing[0].starts_with("Stays"));
+            }
+            other => panic!("expected a WHO listing, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_put_counts_every_item_in_a_stack() {
+        let world = World::new();
+        let id = hero(&world, "Packer");
+        world.spaces.insert(Space::new(&Identifier::origin()));
+
+        let mut pouch = Item::new();
+        pouch.name = "pouch".to_owned();
+        pouch.capacity = 1;
+
+        let mut rocks = Item::new();
+        rocks.name = "rock".to_owned();
+        rocks.prototype = "ROCK".to_owned();
+        rocks.stackable = true;
+        rocks.bulk = 1;
+        rocks.quantity = 3;
+
+        let mut mob = world.mobs.get(&id).unwrap();
+        mob.inventory.add(pouch);
+        mob.inventory.add(rocks);
+        world.mobs.insert(mob);
+
+        let args = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+        let put = |s: &str| tokio_test::block_on(world.put(&id, &args(s)));
+        assert!(put("all rocks in pouch").is_err());
+
+        // the rocks stay put, and the pouch stays empty
+        let mob = world.mobs.get(&id).unwrap();
+        assert_eq!(mob.inventory.count("ROCK"), 3);
+
+        // one fits, and its bulk still counts against the carrier
+        assert!(put("rock in pouch").is_ok());
+        assert!(put("rock in pouch").is_err());
+        let mob = world.mobs.get(&id).unwrap();
+        assert_eq!(mob.inventory.count("ROCK"), 2);
+        assert_eq!(mob.inventory.bulk(), 3);
+    }
+
+    #[test]
+    fn test_disconnecting_calls_off_trades() {
+        let mut world = World::new();
+        let (alice, bob) = (hero(&world, "Alice"), hero(&world, "Bob"));
+        world.trades.propose(&alice, &bob).unwrap();
+        world.trades.accept(&bob).unwrap();
+
+        let updates = world.disconnect(&alice);
+        assert!(world.trades.get(&bob).is_none());
+        assert!(updates.iter().any(|u| u.to == bob));
+    }
+
+    #[test]
+    fn test_crafting_uses_inputs_when_done() {
+        let mut world = World::new();
+        let id = hero(&world, "Maker");
+        world.spaces.insert(Space::new(&Identifier::origin()));
+        world.recipes.insert(
+            "spear".to_owned(),
+            Recipe {
+                name: "spear".to_owned(),
+                inputs: vec![("STICK".to_owned(), 1)],
+                output: "SPEAR".to_owned(),
+                quantity: 1,
+                ..Recipe::default()
+            },
+        );
+
+        let mut spear = ItemPrototype::new();
+        spear.prototype_name = "SPEAR".to_owned();
+        world.item_prototypes.add(spear);
+
+        let mut stick = Item::new();
+        stick.prototype = "STICK".to_owned();
+        stick.name = "stick".to_owned();
+        let mut mob = world.mobs.get(&id).unwrap();
+        mob.constitution = 10;
+        mob.hp = 10;
+        mob.inventory.add(stick);
+        world.mobs.insert(mob);
+
+        let args = vec!["spear".to_owned()];
+        tokio_test::block_on(world.craft(&id, &args)).unwrap();
+
+        // quitting now wouldn't lose the stick
+        let mut mob = world.mobs.get(&id).unwrap();
+        assert_eq!(mob.inventory.count("STICK"), 1);
+
+        mob.delay = None;
+        mob.tick(&world, &mut Dice::seeded(1));
+        assert_eq!(mob.inventory.count("STICK"), 0);
+        assert_eq!(mob.inventory.count("SPEAR"), 1);
+    }
+
+    #[test]
+    fn test_patrol_survives_a_shorter_route() {
+        let world = World::new();
+        world.spaces.insert(Space::new(&Identifier::origin()));
+
+        let mut guard = Mob::new();
+        guard.constitution = 10;
+        guard.hp = 10;
+        guard.movement.patrol = vec![Identifier::random(), Identifier::random()];
+        guard.patrol_step = 3;
+        let id = guard.identifier().clone();
+        world.mobs.insert(guard);
+
+        world.wander(&mut Dice::seeded(1));
+        assert_eq!(world.mobs.get(&id).unwrap().patrol_step, 1);
+    }
+
+    #[test]
+    fn test_poison_can_kill() {
+        let mut world = World::new();
+        let id = hero(&world, "Doomed");
+        let mut space = Space::new(&Identifier::origin());
+        space.population.add(&id);
+        world.spaces.insert(space);
+
+        let mut mob = world.mobs.get(&id).unwrap();
+        mob.constitution = 10;
+        mob.hp = 1;
+        mob.statuses.add(Condition::Poisoned, 5, 1);
+        world.mobs.insert(mob);
+
+        world.tick();
+
+        let mob = world.mobs.get(&id).unwrap();
+        assert!(mob.ghost);
+        let space = world.spaces.get(&Identifier::origin()).unwrap();
+        assert!(space.inventory.items().iter().any(|i| i.is_corpse()));
+    }
+
+    #[test]
+    fn test_recipient() {
+        let words = |s: &str| s.split(' ').map(String::from).collect::<Vec<_>>();
+        let to = |s: &str| {
+            let args = words(s);
+            let (_, rest) = Denomination::parse(&args)?;
+            recipient(&args, rest).cloned()
+        };
+
+        assert_eq!(to("2 gold Bob"), Some("Bob".to_owned()));
+        assert_eq!(to("30 to Bob"), Some("Bob".to_owned()));
+        assert_eq!(to("2 rocks"), None);
+        assert_eq!(to("2 rocks to Bob"), None);
+    }
+
+    #[test]
+    fn test_tells_and_blocks() {
+        let world = World::new();
+        let (alice, bob) = (hero(&world, "Alice"), hero(&world, "Bob"));
+        let say = |s: &str| vec![s.to_owned()];
+
+        assert!(tokio_test::block_on(world.reply(&bob, &say("hi"))).is_err());
+
+        let updates = tokio_test::block_on(world.send_tell(&alice, &bob, "hi")).unwrap();
+        assert_eq!(updates[0].to, bob);
+        assert_eq!(world.mobs.get(&bob).unwrap().reply_to, Some(alice.clone()));
+
+        // REPLY goes back to whoever spoke last
+        let updates = tokio_test::block_on(world.reply(&bob, &say("hello"))).unwrap();
+        assert_eq!(updates[0].to, alice);
+
+        let mut mob = world.mobs.get(&bob).unwrap();
+        mob.block(&alice);
+        world.mobs.insert(mob);
+        assert!(tokio_test::block_on(world.send_tell(&alice, &bob, "hi?")).is_err());
+        assert!(tokio_test::block_on(world.send_tell(&alice, &alice, "hi")).is_err());
+    }
+
+    #[test]
+    fn test_death_loot_and_respawn() {
+        let mut world = World::new();
+        let (victim, looter) = (hero(&world, "Victim"), hero(&world, "Looter"));
+
+        let temple = Identifier::random();
+        world.spaces.insert(Space::new(&temple));
+        let mut space = Space::new(&Identifier::origin());
+        space.recall = Some(temple.clone());
+        space.population.add(&victim);
+        space.population.add(&looter);
+
+        let mut rock = Item::new();
+        rock.name = "rock".to_owned();
+        rock.prototype = "ROCK".to_owned();
+
+        let mut mob = world.mobs.get(&victim).unwrap();
+        mob.constitution = 10;
+        mob.experience = 100;
+        mob.inventory.add(rock);
+        mob.wallet = Wallet::with(50);
+        space.bury(mob, None, &world);
+        world.spaces.insert(space);
+
+        // everything they had is in the corpse, and they're a ghost
+        let ghost = world.mobs.get(&victim).unwrap();
+        assert!(ghost.ghost);
+        assert!(ghost.inventory.is_empty());
+        assert!(ghost.experience < 100);
+        assert!(tokio_test::block_on(world.loot(&victim, None)).is_err());
+
+        let mut mob = world.mobs.get(&looter).unwrap();
+        mob.strength = 10;
+        world.mobs.insert(mob);
+        tokio_test::block_on(world.loot(&looter, None)).unwrap();
+        let mob = world.mobs.get(&looter).unwrap();
+        assert_eq!(mob.inventory.count("ROCK"), 1);
+        assert_eq!(mob.wallet.total(), 50);
+
+        // the dead come back to life at the recall point
+        tokio_test::block_on(world.respawn(&victim)).unwrap();
+        let mob = world.mobs.get(&victim).unwrap();
+        assert!(!mob.ghost && mob.is_alive());
+        assert_eq!(mob.space_id, temple);
+        assert!(tokio_test::block_on(world.respawn(&victim)).is_err());
+    }
+
+    #[test]
+    fn test_heroes_both_have_to_yield() {
+        let world = World::new();
+        let (alice, bob) = (hero(&world, "Alice"), hero(&world, "Bob"));
+        let mut space = Space::new(&Identifier::origin());
+        space.population.add(&alice);
+        space.population.add(&bob);
+        world.spaces.insert(space);
+
+        for (id, enemy) in &[(&alice, &bob), (&bob, &alice)] {
+            let mut mob = world.mobs.get(id).unwrap();
+            mob.constitution = 10;
+            mob.hp = 10;
+            mob.add_enemy(enemy);
+            world.mobs.insert(mob);
+        }
+
+        let to = |name: &str| Some(name.to_owned());
+
+        // offering to yield doesn't end the fight on its own
+        tokio_test::block_on(world.surrender(&alice, to("Bob").as_ref())).unwrap();
+        assert!(world.mobs.get(&alice).unwrap().enemies.contains(&bob));
+        assert!(world.mobs.get(&bob).unwrap().enemies.contains(&alice));
+
+        // but once both agree, neither is fighting the other
+        tokio_test::block_on(world.surrender(&bob, to("Alice").as_ref())).unwrap();
+        for id in &[&alice, &bob] {
+            let mob = world.mobs.get(id).unwrap();
+            assert!(mob.enemies.is_empty());
+            assert!(mob.yielding_to.is_none());
+        }
+
+        // and there's nothing left to yield
+        assert!(tokio_test::block_on(world.surrender(&alice, to("Bob").as_ref())).is_err());
+    }
+
+    #[test]
+    fn test_wimpy_is_capped() {
+        let world = World::new();
+        let id = hero(&world, "Coward");
+        let pct = |p: &str| Some(p.to_owned());
+
+        assert!(tokio_test::block_on(world.wimpy(&id, pct("90%").as_ref())).is_err());
+        tokio_test::block_on(world.wimpy(&id, pct("30%").as_ref())).unwrap();
+
+        let mut mob = world.mobs.get(&id).unwrap();
+        assert_eq!(mob.wimpy, 30);
+        mob.constitution = 10;
+        mob.hp = 2;
+        assert!(mob.is_wimpy());
+    }
+
+    #[test]
+    fn test_npcs_pick_fights() {
+        let world = World::new();
+        let hero_id = hero(&world, "Bait");
+
+        let npc = |prototype: &str, behaviour: Behaviour| {
+            let mut mob = Mob::new();
+            mob.prototype = prototype.to_owned();
+            mob.name = prototype.to_lowercase();
+            mob.behaviour = behaviour;
+            mob.constitution = 10;
+            mob.hp = 10;
+            let id = mob.identifier().clone();
+            world.mobs.insert(mob);
+            id
+        };
+
+        let brawler = npc("RAT", Behaviour::default());
+        let friend = npc(
+            "RAT",
+            Behaviour {
+                assist: true,
+                ..Behaviour::default()
+            },
+        );
+        let biter = npc(
+            "MOSQUITO",
+            Behaviour {
+                aggressive: true,
+                ..Behaviour::default()
+            },
+        );
+        let night_owl = npc(
+            "OWL",
+            Behaviour {
+                aggressive: true,
+                nocturnal: true,
+                ..Behaviour::default()
+            },
+        );
+        let bystander = npc("DEER", Behaviour::default());
+
+        let mut space = Space::new(&Identifier::origin());
+        for id in &[&hero_id, &brawler, &friend, &biter, &night_owl, &bystander] {
+            space.population.add(id);
+        }
+        world.spaces.insert(space);
+
+        let mut hero = world.mobs.get(&hero_id).unwrap();
+        hero.constitution = 100;
+        hero.hp = 100;
+        let mut rat = world.mobs.get(&brawler).unwrap();
+        world.provoke(&mut hero, &mut rat);
+        world.mobs.insert(hero);
+        world.mobs.insert(rat);
+
+        world.melee();
+
+        let fighting = |id: &Identifier| world.mobs.get(id).unwrap().enemies.contains(&hero_id);
+        assert!(fighting(&friend));
+        assert!(fighting(&biter));
+        assert_eq!(fighting(&night_owl), world.clock().is_night());
+        assert!(!fighting(&bystander));
+    }
+
+    #[test]
+    fn test_idle_time() {
+        assert_eq!(idle_time(Duration::from_secs(0)), "0s");
+        assert_eq!(idle_time(Duration::from_secs(59)), "59s");
+        assert_eq!(idle_time(Duration::from_secs(61)), "1m");
+        assert_eq!(idle_time(Duration::from_secs(7300)), "2h");
     }
 }
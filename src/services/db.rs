@@ -1,7 +1,8 @@
 use log::{trace, warn};
 use rusoto_core::Region;
 use rusoto_dynamodb::{
-    AttributeValue, DeleteItemInput, DynamoDb, DynamoDbClient, GetItemInput, PutItemInput,
+    AttributeValue, DeleteItemInput, DynamoDb, DynamoDbClient, GetItemInput, Put, PutItemInput,
+    TransactWriteItem, TransactWriteItemsInput,
 };
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -16,6 +17,8 @@ pub struct Dynamo {
     pub accounts: Table,
     pub sessions: Table,
     pub mobs: Table,
+    pub mailboxes: Table,
+    pub names: Table,
 }
 
 impl fmt::Debug for Dynamo {
@@ -39,6 +42,8 @@ impl Dynamo {
         Self {
             accounts: Table::new(client.clone(), "Accounts", "email"),
             mobs: Table::new(client.clone(), "Mobs", "identifier"),
+            mailboxes: Table::new(client.clone(), "Mailboxes", "identifier"),
+            names: Table::new(client.clone(), "Names", "name"),
             sessions: Table::new(client, "Sessions", "token"),
         }
     }
@@ -93,6 +98,22 @@ impl Table {
             .map(|_| {})
     }
 
+    /// Inserts several records in one transaction: either they're all written, or none are.
+    pub async fn put_all<T: DynamoRecord>(&self, records: &[&T]) -> Result<(), String> {
+        trace!("Table put_all: {:?}", records);
+
+        if !super::service_credentials() {
+            warn!("Table put_all: no service credentials!");
+            return Err("Missing service credentials".to_owned());
+        };
+
+        self.client
+            .transact_write_items(self.build_transaction(records))
+            .await
+            .map_err(|e| format!("Error inserting into {}: {}", self.name, e))
+            .map(|_| {})
+    }
+
     pub async fn delete(&self, pk_value: &str) {
         trace!("Table delete: {:?}", pk_value);
         if !super::service_credentials() {
@@ -132,6 +153,25 @@ impl Table {
         }
     }
 
+    fn build_transaction<T: DynamoRecord>(&self, records: &[&T]) -> TransactWriteItemsInput {
+        let transact_items = records
+            .iter()
+            .map(|record| TransactWriteItem {
+                put: Some(Put {
+                    item: serde_dynamodb::to_hashmap(*record).unwrap(),
+                    table_name: self.name.to_owned(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect();
+
+        TransactWriteItemsInput {
+            transact_items,
+            ..Default::default()
+        }
+    }
+
     fn build_delete_query(&self, pk_value: &str) -> DeleteItemInput {
         let pk = AttributeValue {
             s: Some(pk_value.to_owned()),
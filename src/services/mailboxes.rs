@@ -0,0 +1,25 @@
+use crate::core::Identifier;
+use crate::services::db::{DynamoRecord, HasPrimaryKey};
+use serde::{Deserialize, Serialize};
+
+/// Tells sent to a hero while they were offline, delivered on their next refresh
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Mailbox {
+    pub identifier: Identifier,
+    pub letters: Vec<Letter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Letter {
+    pub from: Identifier,
+    pub name: String,
+    pub message: String,
+}
+
+impl DynamoRecord for Mailbox {}
+
+impl HasPrimaryKey for Mailbox {
+    fn primary_key(&self) -> String {
+        self.identifier.value.to_owned()
+    }
+}
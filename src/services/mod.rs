@@ -1,6 +1,8 @@
 pub mod accounts;
 pub mod db;
 pub mod email;
+pub mod mailboxes;
+pub mod names;
 pub mod sessions;
 
 use rusoto_core::credential::*;
@@ -0,0 +1,43 @@
+use crate::core::Identifier;
+use crate::services::db::{DynamoRecord, HasPrimaryKey};
+use serde::{Deserialize, Serialize};
+
+/// Maps a hero's name to their identifier, so heroes can be found while they're offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeroName {
+    pub name: String,
+    pub identifier: Identifier,
+}
+
+impl HeroName {
+    pub fn new(name: &str, identifier: &Identifier) -> Self {
+        Self {
+            name: Self::normalize(name),
+            identifier: identifier.to_owned(),
+        }
+    }
+
+    /// Names are stored lower case, so lookups are case insensitive
+    pub fn normalize(name: &str) -> String {
+        name.trim().to_lowercase()
+    }
+}
+
+impl DynamoRecord for HeroName {}
+
+impl HasPrimaryKey for HeroName {
+    fn primary_key(&self) -> String {
+        self.name.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_are_case_insensitive() {
+        let hero = HeroName::new(" Hero12 ", &Identifier::random());
+        assert_eq!(hero.primary_key(), HeroName::normalize("HERO12"));
+    }
+}
@@ -1,13 +1,16 @@
-use log::{info, trace};
+use log::{info, trace, warn};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::core::{combat, Attribute, DamageKind, Dice, Resistance, Spawn};
+
 #[derive(Debug)]
 pub enum Kind {
     Space,
     Mob,
     Item,
+    Recipe,
 }
 
 // TODO: Rename Importer to Template
@@ -16,12 +19,25 @@ pub struct Importer {
     pub item: Option<Meta>,
     pub space: Option<Meta>,
     pub mob: Option<Meta>,
+    pub recipe: Option<Recipe>,
     pub description: Description,
     pub actions: Option<HashMap<String, String>>,
     pub exits: Option<HashMap<String, String>>,
     pub items: Option<Vec<crate::core::Spawn>>,
     pub mobs: Option<Vec<crate::core::Spawn>>,
     pub attributes: Option<Attributes>,
+    pub attacks: Option<Vec<Attack>>,
+    pub resistances: Option<HashMap<String, String>>,
+    pub bonuses: Option<HashMap<String, isize>>,
+    pub behaviour: Option<Behaviour>,
+    pub script: Option<String>,
+    pub movement: Option<Movement>,
+    pub dialogue: Option<Vec<DialogueNode>>,
+    pub container: Option<Container>,
+    pub consumable: Option<Consumable>,
+    pub shop: Option<Vec<Ware>>,
+    pub inputs: Option<HashMap<String, usize>>,
+    pub affixes: Option<Vec<Affix>>,
 }
 
 impl Importer {
@@ -47,21 +63,148 @@ impl Importer {
         output
     }
 
+    /// Parses the `[resistances]` table (eg: `fire = "vulnerable"`), skipping anything unknown
+    pub fn resistances(&self) -> Vec<(DamageKind, Resistance)> {
+        let mut output = vec![];
+
+        for (raw_kind, raw_resistance) in self.resistances.iter().flatten() {
+            let kind = DamageKind::from_name(raw_kind);
+            let resistance = Resistance::from_name(raw_resistance);
+
+            match (kind, resistance) {
+                (Some(k), Some(r)) => output.push((k, r)),
+                _ => warn!(
+                    "Unknown resistance {} = {:?}, skipping",
+                    raw_kind, raw_resistance
+                ),
+            }
+        }
+
+        output
+    }
+
+    /// Parses the `[[attacks]]` list, falling back to blunt damage for unknown types
+    pub fn attacks(&self, prototype: &str) -> Vec<crate::core::Attack> {
+        let mut output = vec![];
+
+        for t_attack in self.attacks.iter().flatten() {
+            let kind = DamageKind::from_name(&t_attack.kind).unwrap_or_else(|| {
+                warn!(
+                    "Unknown attack type {:?} for {}, using blunt",
+                    t_attack.kind, prototype
+                );
+                DamageKind::Blunt
+            });
+
+            // a bad damage roll would only turn up mid-fight
+            let damage = match Dice::check(&t_attack.damage) {
+                Ok(_) => t_attack.damage.clone(),
+                Err(_) => {
+                    warn!(
+                        "Bad damage {:?} for {}, using {}",
+                        t_attack.damage,
+                        prototype,
+                        combat::UNARMED
+                    );
+                    combat::UNARMED.to_owned()
+                }
+            };
+
+            output.push(crate::core::Attack {
+                name: t_attack.name.clone(),
+                kind,
+                damage,
+                weight: t_attack.weight.unwrap_or(1),
+            });
+        }
+
+        output
+    }
+
+    /// Parses the `[bonuses]` table (eg: `strength = 2`), skipping unknown attributes
+    pub fn bonuses(&self) -> Vec<(Attribute, isize)> {
+        let mut output = vec![];
+
+        for (raw_attribute, bonus) in self.bonuses.iter().flatten() {
+            match Attribute::from_name(raw_attribute) {
+                Some(a) => output.push((a, *bonus)),
+                None => warn!("Unknown attribute bonus {}, skipping", raw_attribute),
+            }
+        }
+
+        output
+    }
+
+    /// Parses an `[[items]]` or `[[mobs]]` spawn list
+    pub fn spawns(spawns: Option<Vec<Spawn>>, owner: &str) -> Vec<Spawn> {
+        spawns
+            .unwrap_or_default()
+            .into_iter()
+            .map(|mut s| {
+                s.chance = chance(s.chance, &format!("{} in {}", s.name, owner));
+                s
+            })
+            .collect()
+    }
+
     pub fn is_a(&self) -> Kind {
         if self.space.is_some() {
             Kind::Space
         } else if self.mob.is_some() {
             Kind::Mob
+        } else if self.recipe.is_some() {
+            Kind::Recipe
         } else {
             Kind::Item
         }
     }
 }
 
+/// Checks a "1 in `raw`" chance. There's no rolling a 1 in 0, so that becomes 1 in 1.
+pub fn chance(raw: usize, what: &str) -> usize {
+    if raw == 0 {
+        warn!("Chance of 0 for {}, using 1", what);
+        return 1;
+    }
+
+    raw
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Meta {
     pub identifier: String,
     pub name: Option<String>,
+    pub attacks_per_round: Option<usize>,
+    pub recall: Option<String>,
+    pub area: Option<String>,
+    pub slot: Option<String>,
+    pub armour: Option<usize>,
+    pub stackable: Option<bool>,
+    pub weight: Option<usize>,
+    pub bulk: Option<usize>,
+    pub coins: Option<String>,
+    pub durability: Option<String>,
+    pub enchantment: Option<String>,
+    pub binds: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Affix {
+    pub name: String,
+    pub chance: Option<usize>,
+    pub bonuses: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Recipe {
+    pub name: String,
+    pub output: String,
+    pub quantity: Option<usize>,
+    pub tools: Option<Vec<String>>,
+    pub skill: Option<String>,
+    pub level: Option<usize>,
+    pub difficulty: Option<usize>,
+    pub time: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -79,4 +222,79 @@ pub struct Attributes {
     pub charisma: usize,
 }
 
-// TODO: mob 'attacks'
+#[derive(Debug, Deserialize)]
+pub struct Behaviour {
+    pub aggressive: Option<bool>,
+    pub nocturnal: Option<bool>,
+    pub flee_at: Option<usize>,
+    pub assist: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Movement {
+    pub wander: Option<usize>,
+    pub patrol: Option<Vec<String>>,
+    pub area: Option<String>,
+    pub home: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Container {
+    pub capacity: usize,
+    pub closed: Option<bool>,
+    pub locked: Option<bool>,
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Consumable {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub heal: Option<usize>,
+    pub antidote: Option<usize>,
+    pub restore: Option<bool>,
+    pub bless: Option<Bless>,
+    pub teleport: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Bless {
+    pub potency: usize,
+    pub ticks: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Ware {
+    pub name: String,
+    pub price: usize,
+    pub chance: Option<usize>,
+    pub max: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DialogueNode {
+    pub id: String,
+    pub text: String,
+    pub choices: Option<Vec<DialogueChoice>>,
+    pub give_item: Option<String>,
+    pub set_flag: Option<String>,
+    pub start_quest: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DialogueChoice {
+    pub label: String,
+    pub goto: String,
+    pub requires_item: Option<String>,
+    pub requires_flag: Option<String>,
+    pub unless_flag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Attack {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub damage: String,
+    pub weight: Option<usize>,
+}
@@ -1,5 +1,7 @@
 use crate::core::*;
-use crate::templates::Importer;
+use crate::templates::importer::{chance, Importer};
+
+use log::warn;
 
 #[derive(Debug, Default)]
 pub struct Injector {
@@ -15,15 +17,136 @@ impl Injector {
 impl From<Importer> for Injector {
     fn from(template: Importer) -> Self {
         let mut injector = Injector::default();
-        let t_item = template.item.unwrap();
+        injector.prototype.resistances = template.resistances();
+        injector.prototype.bonuses = template.bonuses();
+
+        let t_item = template.item.as_ref().unwrap();
+        injector.prototype.attacks = template.attacks(&t_item.identifier);
+        injector.prototype.armour = t_item.armour.unwrap_or(0);
+        injector.prototype.stackable = t_item.stackable.unwrap_or(false);
+        injector.prototype.weight = t_item.weight.unwrap_or(1);
+        injector.prototype.bulk = t_item.bulk.unwrap_or(1);
+        injector.prototype.durability = roll(&t_item.durability, "durability", &t_item.identifier);
+        injector.prototype.enchantment =
+            roll(&t_item.enchantment, "enchantment", &t_item.identifier);
+        injector.prototype.binds = t_item.binds.unwrap_or(false);
+
+        if let Some(raw_slot) = &t_item.slot {
+            injector.prototype.slot = Slot::from_name(raw_slot);
+            if injector.prototype.slot.is_none() {
+                warn!("Unknown slot {:?} for {}", raw_slot, t_item.identifier);
+            }
+        }
+
+        if let Some(t_container) = &template.container {
+            injector.prototype.capacity = t_container.capacity;
+            injector.prototype.key = t_container.key.clone();
+
+            // a locked container has to start out closed
+            injector.prototype.locked = t_container.locked.unwrap_or(false);
+            injector.prototype.closed =
+                t_container.closed.unwrap_or(false) || injector.prototype.locked;
+        }
+
+        if let Some(t_consumable) = &template.consumable {
+            match ConsumableKind::from_name(&t_consumable.kind) {
+                Some(kind) => {
+                    let mut effects = vec![];
+                    if let Some(hp) = t_consumable.heal {
+                        effects.push(ItemEffect::Heal(hp));
+                    }
+                    if let Some(hp) = t_consumable.antidote {
+                        effects.push(ItemEffect::Antidote(hp));
+                    }
+                    if t_consumable.restore.unwrap_or(false) {
+                        effects.push(ItemEffect::Restore);
+                    }
+                    if let Some(bless) = &t_consumable.bless {
+                        effects.push(ItemEffect::Bless {
+                            potency: bless.potency,
+                            ticks: bless.ticks,
+                        });
+                    }
+                    if let Some(space) = &t_consumable.teleport {
+                        effects.push(ItemEffect::Teleport(Identifier::from(space.as_str())));
+                    }
+
+                    injector.prototype.consumable = Some(Consumable { kind, effects });
+                }
+                None => warn!(
+                    "Unknown consumable type {:?} for {}",
+                    t_consumable.kind, t_item.identifier
+                ),
+            }
+        }
+
+        for t_affix in template.affixes.iter().flatten() {
+            let mut bonuses = vec![];
+            for (raw_attribute, roll) in t_affix.bonuses.iter().flatten() {
+                let attribute = match Attribute::from_name(raw_attribute) {
+                    Some(a) => a,
+                    None => {
+                        warn!(
+                            "Unknown attribute {} on affix {}, skipping",
+                            raw_attribute, t_affix.name
+                        );
+                        continue;
+                    }
+                };
+
+                if Dice::check(roll).is_err() {
+                    warn!(
+                        "Bad {} roll {:?} on affix {}, skipping",
+                        raw_attribute, roll, t_affix.name
+                    );
+                    continue;
+                }
 
-        if let Some(name) = t_item.name {
-            injector.prototype.name = name;
+                bonuses.push((attribute, roll.clone()));
+            }
+
+            injector.prototype.affixes.push(AffixRoll {
+                name: t_affix.name.clone(),
+                chance: chance(t_affix.chance.unwrap_or(1), &t_affix.name),
+                bonuses,
+            });
+        }
+
+        if let Some(name) = &t_item.name {
+            injector.prototype.name = name.clone();
         }
 
-        injector.prototype.prototype_name = t_item.identifier;
-        injector.prototype.description.text = template.description.day;
+        injector.prototype.prototype_name = t_item.identifier.clone();
+        injector.prototype.description.text = template.description.day.clone();
 
         injector
     }
 }
+
+/// Checks an optional roll string, leaving out (with a warning) any that can't be rolled
+fn roll(raw: &Option<String>, what: &str, identifier: &str) -> Option<String> {
+    let roll = raw.as_ref()?;
+    match Dice::check(roll) {
+        Ok(_) => Some(roll.clone()),
+        Err(_) => {
+            warn!("Bad {} {:?} for {}, ignoring it", what, roll, identifier);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_spear_has_durability() {
+        let template = Importer::from_file(&PathBuf::from("world/items/spear.toml"));
+        let prototype = Injector::from(template).prototype;
+
+        let spear = prototype.create(&mut Dice::seeded(7));
+        assert!(spear.max_durability > 0);
+        assert_eq!(spear.durability, spear.max_durability);
+    }
+}
@@ -1,13 +1,31 @@
+use crate::core::dialogue::{Choice, Consequence, Node, Requirement};
+use crate::core::shop::Ware;
 use crate::core::*;
-use crate::templates::Importer;
+use crate::templates::importer::{chance, Importer};
+
+use log::warn;
 
 #[derive(Debug, Default)]
 pub struct Injector {
     pub prototype: MobPrototype,
+    pub script: Option<String>,
+    pub dialogue: Option<Dialogue>,
 }
 
 impl Injector {
     pub fn inject(self, world: &mut World) {
+        if let Some(source) = &self.script {
+            if let Err(e) = world.scripts.load(&self.prototype.prototype_name, source) {
+                warn!("Skipping script: {:?}", e);
+            }
+        }
+
+        if let Some(dialogue) = self.dialogue {
+            world
+                .dialogues
+                .insert(self.prototype.prototype_name.clone(), dialogue);
+        }
+
         world.mob_prototypes.add(self.prototype);
     }
 }
@@ -15,14 +33,20 @@ impl Injector {
 impl From<Importer> for Injector {
     fn from(template: Importer) -> Self {
         let mut injector = Injector::default();
-        let t_item = template.mob.unwrap();
+        injector.prototype.resistances = template.resistances();
+
+        let t_item = template.mob.as_ref().unwrap();
+        injector.prototype.attacks = template.attacks(&t_item.identifier);
 
-        if let Some(name) = t_item.name {
-            injector.prototype.name = name;
+        if let Some(name) = &t_item.name {
+            injector.prototype.name = name.clone();
         }
 
-        injector.prototype.prototype_name = t_item.identifier;
+        injector.prototype.prototype_name = t_item.identifier.clone();
+        injector.prototype.attacks_per_round = t_item.attacks_per_round.unwrap_or(1);
+        injector.prototype.coins = t_item.coins.clone();
         injector.prototype.description.text = template.description.day.clone();
+        injector.script = template.script.clone();
 
         if let Some(attrs) = template.attributes {
             injector.prototype.strength = attrs.strength;
@@ -36,6 +60,99 @@ impl From<Importer> for Injector {
             injector.prototype.hp = attrs.constitution;
         }
 
+        if let Some(t_behaviour) = template.behaviour {
+            injector.prototype.behaviour = Behaviour {
+                aggressive: t_behaviour.aggressive.unwrap_or(false),
+                nocturnal: t_behaviour.nocturnal.unwrap_or(false),
+                assist: t_behaviour.assist.unwrap_or(false),
+            };
+
+            // fleeing at low health works the same as a hero's wimpy threshold
+            injector.prototype.wimpy = t_behaviour.flee_at.unwrap_or(0);
+        }
+
+        if let Some(t_movement) = template.movement {
+            injector.prototype.movement = Movement {
+                wander: t_movement.wander.unwrap_or(0),
+                patrol: t_movement
+                    .patrol
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Identifier::from)
+                    .collect(),
+                area: t_movement.area,
+                home: t_movement.home.unwrap_or(false),
+            };
+        }
+
+        if let Some(t_wares) = template.shop {
+            let wares = t_wares
+                .into_iter()
+                .map(|t_ware| Ware {
+                    restock: Spawn {
+                        chance: chance(t_ware.chance.unwrap_or(1), &t_ware.name),
+                        name: t_ware.name,
+                        max: t_ware.max.unwrap_or(1),
+                    },
+                    price: t_ware.price,
+                })
+                .collect();
+
+            injector.prototype.shop = Some(Shop { wares });
+        }
+
+        if let Some(t_nodes) = template.dialogue {
+            let mut dialogue = Dialogue::default();
+
+            for t_node in t_nodes {
+                let mut node = Node {
+                    text: t_node.text,
+                    ..Node::default()
+                };
+
+                for t_choice in t_node.choices.unwrap_or_default() {
+                    let mut requirements = vec![];
+                    if let Some(item) = t_choice.requires_item {
+                        requirements.push(Requirement::Item(item));
+                    }
+                    if let Some(flag) = t_choice.requires_flag {
+                        requirements.push(Requirement::Flag(flag));
+                    }
+                    if let Some(flag) = t_choice.unless_flag {
+                        requirements.push(Requirement::NotFlag(flag));
+                    }
+
+                    node.choices.push(Choice {
+                        label: t_choice.label,
+                        goto: t_choice.goto,
+                        requirements,
+                    });
+                }
+
+                if let Some(item) = t_node.give_item {
+                    node.consequences.push(Consequence::GiveItem(item));
+                }
+                if let Some(flag) = t_node.set_flag {
+                    node.consequences.push(Consequence::SetFlag(flag));
+                }
+                if let Some(quest) = t_node.start_quest {
+                    node.consequences.push(Consequence::StartQuest(quest));
+                }
+
+                dialogue.nodes.insert(t_node.id, node);
+            }
+
+            if dialogue.node(dialogue::START).is_none() {
+                warn!(
+                    "Dialogue for {} has no {:?} node",
+                    injector.prototype.prototype_name,
+                    dialogue::START
+                );
+            }
+
+            injector.dialogue = Some(dialogue);
+        }
+
         injector
     }
 }
@@ -2,6 +2,7 @@ mod importer;
 
 pub mod item;
 pub mod mob;
+pub mod recipe;
 pub mod space;
 
 use crate::core::World;
@@ -14,5 +15,6 @@ pub fn bootstrap(path: &str, world: &mut World) {
             Kind::Space => space::Injector::from(t).inject(world),
             Kind::Mob => mob::Injector::from(t).inject(world),
             Kind::Item => item::Injector::from(t).inject(world),
+            Kind::Recipe => recipe::Injector::from(t).inject(world),
         });
 }
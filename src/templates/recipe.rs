@@ -0,0 +1,53 @@
+use crate::core::*;
+use crate::templates::Importer;
+
+use log::warn;
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+pub struct Injector {
+    pub recipe: Recipe,
+}
+
+impl Injector {
+    pub fn inject(self, world: &mut World) {
+        world
+            .recipes
+            .insert(self.recipe.name.to_lowercase(), self.recipe);
+    }
+}
+
+impl From<Importer> for Injector {
+    fn from(template: Importer) -> Self {
+        let mut injector = Injector::default();
+
+        let t_recipe = template.recipe.unwrap();
+        injector.recipe.name = t_recipe.name.clone();
+        injector.recipe.description = template.description.day.clone();
+        injector.recipe.output = t_recipe.output;
+        injector.recipe.quantity = t_recipe.quantity.unwrap_or(1);
+        injector.recipe.tools = t_recipe.tools.unwrap_or_default();
+        injector.recipe.difficulty = t_recipe.difficulty.unwrap_or(0);
+        injector.recipe.time = Duration::from_secs(t_recipe.time.unwrap_or(1));
+
+        // sorted, so messages about missing inputs are predictable
+        let mut inputs: Vec<(String, usize)> =
+            template.inputs.unwrap_or_default().into_iter().collect();
+        inputs.sort();
+        injector.recipe.inputs = inputs;
+
+        if let Some(raw_skill) = &t_recipe.skill {
+            match Attribute::from_name(raw_skill) {
+                Some(attribute) => {
+                    injector.recipe.skill = Some((attribute, t_recipe.level.unwrap_or(0)))
+                }
+                None => warn!(
+                    "Unknown skill {:?} for recipe {}, skipping",
+                    raw_skill, t_recipe.name
+                ),
+            }
+        }
+
+        injector
+    }
+}
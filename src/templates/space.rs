@@ -24,8 +24,10 @@ impl Injector {
 
 impl From<Importer> for Injector {
     fn from(template: Importer) -> Self {
-        let identifier = Identifier::from(template.space.unwrap().identifier);
-        let mut injector = Injector::new(identifier);
+        let meta = template.space.unwrap();
+        let mut injector = Injector::new(Identifier::from(meta.identifier));
+        injector.space.recall = meta.recall.map(Identifier::from);
+        injector.space.area = meta.area;
 
         for (raw_direction, raw_id) in template.exits.unwrap() {
             let exit_id = Identifier::from(raw_id);
@@ -33,8 +35,9 @@ impl From<Importer> for Injector {
             injector.space.exits.insert(direction, exit_id);
         }
 
-        injector.space.item_spawn = template.items.unwrap_or_default();
-        injector.space.mob_spawn = template.mobs.unwrap_or_default();
+        let owner = injector.id().to_string();
+        injector.space.item_spawn = Importer::spawns(template.items, &owner);
+        injector.space.mob_spawn = Importer::spawns(template.mobs, &owner);
         injector.space.description.text = template.description.day.clone();
 
         for (label, action) in template.actions.unwrap() {